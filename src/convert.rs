@@ -0,0 +1,49 @@
+//! This module converts instances between the formats that can be read, e.g.
+//! to share them with people who work with spreadsheets.
+
+use std::fs;
+
+use clap::Args;
+
+use crate::format::InstanceFormat;
+use crate::source::{FileSource, InstanceSource, StdinSource, TextSource};
+
+#[derive(Debug, Args)]
+pub struct Convert {
+    /// The path to the instance to convert, - for the standard input
+    #[clap(long)]
+    pub from: String,
+    /// The path where to write the converted instance, - for the standard output
+    #[clap(long)]
+    pub to: String,
+    /// The format of the instance to convert
+    #[clap(long, value_enum, default_value="json")]
+    pub input_format: InstanceFormat,
+    /// The format of the converted instance
+    #[clap(long, value_enum, default_value="json")]
+    pub output_format: InstanceFormat,
+}
+
+impl Convert {
+    pub fn convert(&self) -> Result<(), String> {
+        let path = Some(self.from.clone()).filter(|p| p != "-");
+        let source: Box<dyn InstanceSource> = match (path, self.input_format) {
+            (Some(path), InstanceFormat::Json) => Box::new(FileSource(path)),
+            (None, InstanceFormat::Json) => Box::new(StdinSource),
+            (path, format) => Box::new(TextSource(path, format)),
+        };
+        let instance = source.load()?;
+        instance.check_dimensions().map_err(|e| format!("invalid instance: {e}"))?;
+
+        if self.output_format == InstanceFormat::Talent && instance.contiguous.contains(&true) {
+            eprintln!("warning: the talent format has no contiguity constraints, they are dropped");
+        }
+
+        match (self.to.as_str(), self.output_format) {
+            ("-", format) => println!("{}", format.format(&instance).trim_end()),
+            (path, InstanceFormat::Json) => instance.try_write(path)?,
+            (path, format) => fs::write(path, format.format(&instance)).map_err(|e| format!("cannot write {path}: {e}"))?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! This module compares two schedules of the same instance.
+
+use clap::Args;
+
+use crate::instance::TalentSchedInstance;
+use crate::solution::read_solution;
+
+#[derive(Debug, Args)]
+pub struct Diff {
+    /// The path to the instance file
+    #[clap(short, long)]
+    pub instance: String,
+    /// The path to the first solution file
+    pub first: String,
+    /// The path to the second solution file
+    pub second: String,
+    /// Scenes that moved by at least this many positions are highlighted
+    #[clap(long, default_value="3")]
+    pub threshold: usize,
+}
+
+impl Diff {
+    /// Prints the positions where the schedules differ, the scenes that moved
+    /// the most, the constraints each schedule violates and their costs.
+    /// Returns whether both schedules satisfy all the constraints.
+    pub fn diff(&self) -> Result<bool, String> {
+        let instance = TalentSchedInstance::try_read(&self.instance)?;
+        instance.check_dimensions()?;
+
+        let first = read_solution(&self.first)?;
+        let second = read_solution(&self.second)?;
+
+        println!("{:>8} {:>6} {:>6}", "position", "first", "second");
+        for position in 0..first.len().max(second.len()) {
+            let a = first.get(position);
+            let b = second.get(position);
+            if a != b {
+                println!("{:>8} {:>6} {:>6}", position, Self::show(a), Self::show(b));
+            }
+        }
+
+        for (position, scene) in first.iter().enumerate() {
+            if let Some(other) = second.iter().position(|s| s == scene) {
+                let moved = position.abs_diff(other);
+                if moved >= self.threshold {
+                    println!("scene {scene} moved by {moved} positions ({position} -> {other})");
+                }
+            }
+        }
+
+        let first_valid = Self::report_violations(&instance, "first", &first);
+        let second_valid = Self::report_violations(&instance, "second", &second);
+        if !first_valid || !second_valid {
+            return Ok(false);
+        }
+
+        let first_cost = instance.schedule_cost(&first) as isize;
+        let second_cost = instance.schedule_cost(&second) as isize;
+        println!("first cost {first_cost}");
+        println!("second cost {second_cost}");
+        println!("delta {}", second_cost - first_cost);
+        Ok(true)
+    }
+
+    /// Prints each constraint violated by the schedule, returns whether there
+    /// are none
+    fn report_violations(instance: &TalentSchedInstance, name: &str, order: &[usize]) -> bool {
+        let violations = instance.violations(order);
+        for violation in violations.iter() {
+            println!("{name} violation: {violation}");
+        }
+        if !violations.is_empty() {
+            println!("{name} invalid: {} violation(s)", violations.len());
+        }
+        violations.is_empty()
+    }
+
+    fn show(scene: Option<&usize>) -> String {
+        scene.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string())
+    }
+}
@@ -0,0 +1,217 @@
+//! This module exposes a minimal C API to embed the solver, with the `cffi`
+//! feature. Instances and results are opaque pointers that must be released
+//! with their respective free function.
+
+use std::{panic::{self, AssertUnwindSafe}, ptr, slice, time::Duration};
+
+use crate::instance::TalentSchedInstance;
+use crate::resolution::{SolveBuilder, SolveResult};
+
+/// Returned by `ts_solve` when the solve succeeded
+pub const TS_OK: i32 = 0;
+/// Returned by `ts_solve` when the instance, the config or the output pointer is null
+pub const TS_NULL_POINTER: i32 = 1;
+/// Returned by `ts_solve` when the instance is not valid, e.g. too large
+pub const TS_INVALID_INSTANCE: i32 = 2;
+/// Returned by `ts_solve` when the solver panicked
+pub const TS_SOLVER_ERROR: i32 = 3;
+
+/// The settings of a solve. A zero width keeps the default width of 100,
+/// while zero or less for the timeout and zero for the node limit mean no
+/// limit, and zero threads uses all logical cores.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TsConfig {
+    /// The maximum number of nodes in a layer of the decision diagrams, zero
+    /// keeps the default
+    pub width: usize,
+    /// The timeout in seconds, zero or less means no time limit
+    pub timeout: f64,
+    /// The maximum number of expanded nodes, zero means no limit
+    pub max_nodes: usize,
+    /// The number of threads, zero uses all logical cores
+    pub threads: usize,
+}
+
+/// Builds an instance from arrays: `cost` has `nb_actors` entries, `duration`
+/// has `nb_scenes` entries and `actors` is the `nb_actors` x `nb_scenes`
+/// presence matrix stored row by row. Returns a null pointer if an array is
+/// null or if the size of the matrix overflows.
+///
+/// # Safety
+/// The arrays must be valid for reads of the given sizes.
+#[no_mangle]
+pub unsafe extern "C" fn ts_instance_new(nb_scenes: usize, nb_actors: usize, cost: *const usize, duration: *const usize, actors: *const usize) -> *mut TalentSchedInstance {
+    if cost.is_null() || duration.is_null() || actors.is_null() {
+        return ptr::null_mut();
+    }
+    let nb_entries = match nb_actors.checked_mul(nb_scenes) {
+        Some(nb_entries) => nb_entries,
+        None => return ptr::null_mut(),
+    };
+
+    let cost = slice::from_raw_parts(cost, nb_actors).to_vec();
+    let duration = slice::from_raw_parts(duration, nb_scenes).to_vec();
+    let actors = slice::from_raw_parts(actors, nb_entries)
+        .chunks(nb_scenes.max(1))
+        .take(nb_actors)
+        .map(|row| row.to_vec())
+        .collect();
+
+    Box::into_raw(Box::new(TalentSchedInstance { nb_scenes, nb_actors, cost, duration, actors, contiguous: vec![] }))
+}
+
+/// Releases an instance created by `ts_instance_new`
+///
+/// # Safety
+/// The pointer must come from `ts_instance_new` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ts_instance_free(instance: *mut TalentSchedInstance) {
+    if !instance.is_null() {
+        drop(Box::from_raw(instance));
+    }
+}
+
+/// Solves the instance with the given settings and stores the result in
+/// `result`. Returns `TS_OK` on success, and otherwise one of the error codes
+/// above, in which case `result` is set to a null pointer.
+///
+/// # Safety
+/// The instance must come from `ts_instance_new`, the config must be a valid
+/// pointer and `result` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn ts_solve(instance: *const TalentSchedInstance, config: *const TsConfig, result: *mut *mut SolveResult) -> i32 {
+    if instance.is_null() || config.is_null() || result.is_null() {
+        return TS_NULL_POINTER;
+    }
+    *result = ptr::null_mut();
+
+    let instance = &*instance;
+    let builder = builder(*config);
+
+    // a panic must not unwind across the C boundary
+    match panic::catch_unwind(AssertUnwindSafe(|| builder.run(instance))) {
+        Ok(Ok(solved)) => {
+            *result = Box::into_raw(Box::new(solved));
+            TS_OK
+        },
+        Ok(Err(_)) => TS_INVALID_INSTANCE,
+        Err(_) => TS_SOLVER_ERROR,
+    }
+}
+
+fn builder(config: TsConfig) -> SolveBuilder {
+    let mut builder = SolveBuilder::new().threads(config.threads).without_timeout();
+    if config.width > 0 {
+        builder = builder.width(config.width);
+    }
+    if config.timeout > 0.0 {
+        builder = builder.timeout(Duration::from_secs_f64(config.timeout));
+    }
+    if config.max_nodes > 0 {
+        builder = builder.max_nodes(config.max_nodes);
+    }
+    builder
+}
+
+/// Returns whether the best solution is proven optimal, false if the result
+/// is null
+///
+/// # Safety
+/// The result must come from `ts_solve` or be null.
+#[no_mangle]
+pub unsafe extern "C" fn ts_result_is_exact(result: *const SolveResult) -> bool {
+    result.as_ref().map(|r| r.is_exact).unwrap_or(false)
+}
+
+/// Returns the cost of the best solution, or -1 if none was found or if the
+/// result is null
+///
+/// # Safety
+/// The result must come from `ts_solve` or be null.
+#[no_mangle]
+pub unsafe extern "C" fn ts_result_cost(result: *const SolveResult) -> isize {
+    result.as_ref().and_then(|r| r.best_value).unwrap_or(-1)
+}
+
+/// Returns a lower bound on the optimal cost, or -1 if the result is null
+///
+/// # Safety
+/// The result must come from `ts_solve` or be null.
+#[no_mangle]
+pub unsafe extern "C" fn ts_result_bound(result: *const SolveResult) -> isize {
+    result.as_ref().map(|r| r.best_bound).unwrap_or(-1)
+}
+
+/// Copies the scenes of the best schedule, in shooting order, to `schedule`
+/// which must have room for `nb_scenes` entries. Returns the number of scenes
+/// copied, 0 if no solution was found or if the result is null.
+///
+/// # Safety
+/// The result must come from `ts_solve` or be null, and `schedule` must be
+/// valid for writes of `nb_scenes` entries.
+#[no_mangle]
+pub unsafe extern "C" fn ts_result_schedule(result: *const SolveResult, schedule: *mut usize) -> usize {
+    let solution = match result.as_ref() {
+        Some(result) => &result.solution,
+        None => return 0,
+    };
+    if !schedule.is_null() {
+        slice::from_raw_parts_mut(schedule, solution.len()).copy_from_slice(solution);
+    }
+    solution.len()
+}
+
+/// Releases a result created by `ts_solve`
+///
+/// # Safety
+/// The pointer must come from `ts_solve` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ts_result_free(result: *mut SolveResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: TsConfig = TsConfig { width: 0, timeout: 10.0, max_nodes: 0, threads: 1 };
+
+    #[test]
+    fn solves_a_small_instance() {
+        let (cost, duration, actors) = ([2, 3], [1, 2, 1], [1, 0, 1, 0, 1, 1]);
+        unsafe {
+            let instance = ts_instance_new(3, 2, cost.as_ptr(), duration.as_ptr(), actors.as_ptr());
+            let mut result = ptr::null_mut();
+            assert_eq!(ts_solve(instance, &CONFIG, &mut result), TS_OK);
+
+            let mut schedule = [0; 3];
+            assert_eq!(ts_result_schedule(result, schedule.as_mut_ptr()), 3);
+            assert_eq!(ts_result_cost(result), (*instance).schedule_cost(&schedule) as isize);
+
+            ts_result_free(result);
+            ts_instance_free(instance);
+        }
+    }
+
+    #[test]
+    fn reports_errors() {
+        let too_many = [1; 65];
+        unsafe {
+            assert!(ts_instance_new(usize::MAX, 2, too_many.as_ptr(), too_many.as_ptr(), too_many.as_ptr()).is_null());
+
+            let instance = ts_instance_new(65, 1, too_many.as_ptr(), too_many.as_ptr(), too_many.as_ptr());
+            let mut result = ptr::null_mut();
+            assert_eq!(ts_solve(instance, &CONFIG, &mut result), TS_INVALID_INSTANCE);
+            assert!(result.is_null());
+            assert_eq!(ts_solve(instance, ptr::null(), &mut result), TS_NULL_POINTER);
+            ts_instance_free(instance);
+
+            assert!(!ts_result_is_exact(ptr::null()));
+            assert_eq!(ts_result_cost(ptr::null()), -1);
+            assert_eq!(ts_result_schedule(ptr::null(), ptr::null_mut()), 0);
+        }
+    }
+}
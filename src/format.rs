@@ -0,0 +1,212 @@
+//! This module reads and writes instances in text formats other than JSON.
+//!
+//! The talent format is the plain-text format of the classic talent
+//! scheduling benchmarks: the number of actors and scenes,
+//! then the actor matrix with one row per actor, then the daily cost of each
+//! actor and finally the duration of each scene, e.g.
+//!
+//! ```text
+//! 2 3
+//! 1 0 1
+//! 0 1 1
+//! 20 10
+//! 1 2 1
+//! ```
+//!
+//! The values only need to be separated by whitespaces, the line breaks are
+//! not significant. The format has no contiguity constraints.
+//!
+//! The CSV format is meant for spreadsheets: a header with the index of each
+//! scene, one row per actor with their daily cost (and whether their scenes
+//! must be contiguous) in the trailing columns, then a row with the duration
+//! of each scene. The unknown entries of the actor matrix are left empty.
+//!
+//! ```text
+//! actor,0,1,2,cost
+//! 0,1,0,1,20
+//! 1,0,1,1,10
+//! duration,1,2,1,
+//! ```
+
+use clap::ValueEnum;
+
+use crate::instance::{TalentSchedInstance, UNKNOWN};
+
+/// The format of an instance file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InstanceFormat {
+    /// JSON, or bincode when the file ends with .bin
+    Json,
+    /// The plain-text format of the classic benchmarks
+    Talent,
+    /// A spreadsheet with the actor matrix, the costs and the durations
+    Csv,
+}
+
+impl InstanceFormat {
+    /// Parses an instance written in this format
+    pub fn parse(&self, text: &str) -> Result<TalentSchedInstance, String> {
+        match self {
+            InstanceFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            InstanceFormat::Talent => parse_talent(text),
+            InstanceFormat::Csv => parse_csv(text),
+        }
+    }
+
+    /// Writes an instance in this format
+    pub fn format(&self, instance: &TalentSchedInstance) -> String {
+        match self {
+            InstanceFormat::Json => serde_json::to_string_pretty(instance).unwrap(),
+            InstanceFormat::Talent => to_talent(instance),
+            InstanceFormat::Csv => to_csv(instance),
+        }
+    }
+}
+
+/// Parses an instance in the talent format
+pub fn parse_talent(text: &str) -> Result<TalentSchedInstance, String> {
+    let mut values = text.split_whitespace().enumerate().map(|(i, token)| {
+        token.parse::<usize>().map_err(|_| format!("invalid value {token} at position {i}"))
+    });
+    let mut next = |what: &str| values.next().unwrap_or_else(|| Err(format!("missing {what}")));
+
+    let nb_actors = next("number of actors")?;
+    let nb_scenes = next("number of scenes")?;
+
+    let mut actors = vec![vec![0; nb_scenes]; nb_actors];
+    for (actor, row) in actors.iter_mut().enumerate() {
+        for x in row.iter_mut() {
+            *x = next(&format!("row of actor {actor}"))?;
+        }
+    }
+    let cost = (0..nb_actors).map(|_| next("actor cost")).collect::<Result<Vec<usize>, String>>()?;
+    let duration = (0..nb_scenes).map(|_| next("scene duration")).collect::<Result<Vec<usize>, String>>()?;
+
+    if let Some(value) = values.next() {
+        return Err(format!("unexpected value {} after the scene durations", value?));
+    }
+
+    Ok(TalentSchedInstance { nb_scenes, nb_actors, cost, duration, actors, contiguous: vec![] })
+}
+
+/// Writes an instance in the talent format, its contiguity constraints are lost
+pub fn to_talent(instance: &TalentSchedInstance) -> String {
+    let row = |values: &[usize]| values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" ");
+
+    let mut text = format!("{} {}\n", instance.nb_actors, instance.nb_scenes);
+    for actor in instance.actors.iter() {
+        text.push_str(&row(actor));
+        text.push('\n');
+    }
+    text.push_str(&row(&instance.cost));
+    text.push('\n');
+    text.push_str(&row(&instance.duration));
+    text.push('\n');
+    text
+}
+
+/// Parses an instance in the CSV format
+pub fn parse_csv(text: &str) -> Result<TalentSchedInstance, String> {
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let cells = |line: &str| line.split(',').map(|c| c.trim().to_string()).collect::<Vec<String>>();
+    let number = |cell: &str, line: usize| cell.parse::<usize>().map_err(|_| format!("invalid value {cell} on line {}", line + 1));
+
+    let header = cells(lines.next().ok_or("missing header")?.1);
+    let with_contiguous = header.last().map(|c| c == "contiguous").unwrap_or(false);
+    let nb_extra = if with_contiguous { 2 } else { 1 };
+    if header.len() < 1 + nb_extra || header[header.len() - nb_extra] != "cost" {
+        return Err("the header must end with a cost column".to_string());
+    }
+    let nb_scenes = header.len() - 1 - nb_extra;
+
+    let mut actors = vec![];
+    let mut cost = vec![];
+    let mut contiguous = vec![];
+    let mut duration = None;
+    for (i, line) in lines {
+        let row = cells(line);
+        if row.len() != header.len() {
+            return Err(format!("line {} has {} columns instead of {}", i + 1, row.len(), header.len()));
+        }
+        if duration.is_some() {
+            return Err(format!("unexpected line {} after the durations", i + 1));
+        }
+
+        if row[0] == "duration" {
+            duration = Some(row[1..=nb_scenes].iter().map(|c| number(c, i)).collect::<Result<Vec<usize>, String>>()?);
+        } else {
+            actors.push(row[1..=nb_scenes].iter()
+                .map(|c| if c.is_empty() { Ok(UNKNOWN) } else { number(c, i) })
+                .collect::<Result<Vec<usize>, String>>()?);
+            cost.push(number(&row[nb_scenes + 1], i)?);
+            if with_contiguous {
+                contiguous.push(number(&row[nb_scenes + 2], i)? != 0);
+            }
+        }
+    }
+
+    Ok(TalentSchedInstance {
+        nb_scenes,
+        nb_actors: actors.len(),
+        cost,
+        duration: duration.ok_or("missing duration row")?,
+        actors,
+        contiguous,
+    })
+}
+
+/// Writes an instance in the CSV format
+pub fn to_csv(instance: &TalentSchedInstance) -> String {
+    let with_contiguous = !instance.contiguous.is_empty();
+    let entry = |x: &usize| if *x == UNKNOWN { String::new() } else { x.to_string() };
+
+    let mut header = vec!["actor".to_string()];
+    header.extend((0..instance.nb_scenes).map(|s| s.to_string()));
+    header.push("cost".to_string());
+    if with_contiguous {
+        header.push("contiguous".to_string());
+    }
+    let mut text = header.join(",");
+    text.push('\n');
+
+    for (actor, row) in instance.actors.iter().enumerate() {
+        let mut cells = vec![actor.to_string()];
+        cells.extend(row.iter().map(entry));
+        cells.push(instance.cost[actor].to_string());
+        if with_contiguous {
+            cells.push(if instance.contiguous.get(actor).copied().unwrap_or(false) { "1" } else { "0" }.to_string());
+        }
+        text.push_str(&cells.join(","));
+        text.push('\n');
+    }
+
+    let mut cells = vec!["duration".to_string()];
+    cells.extend(instance.duration.iter().map(|d| d.to_string()));
+    cells.extend(std::iter::repeat(String::new()).take(if with_contiguous { 2 } else { 1 }));
+    text.push_str(&cells.join(","));
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_with_a_short_contiguous_vector() {
+        let instance = TalentSchedInstance {
+            nb_scenes: 2,
+            nb_actors: 2,
+            cost: vec![3, 5],
+            duration: vec![1, 2],
+            actors: vec![vec![1, 0], vec![1, 1]],
+            contiguous: vec![true],
+        };
+
+        let parsed = parse_csv(&to_csv(&instance)).unwrap();
+        assert_eq!(parsed.contiguous, vec![true, false]);
+        assert_eq!(parsed.actors, instance.actors);
+        assert_eq!(parsed.cost, instance.cost);
+        assert_eq!(parsed.duration, instance.duration);
+    }
+}
@@ -1,132 +1,378 @@
-use std::{time::{SystemTime, UNIX_EPOCH}, fs::File, io::Write};
+use std::{fs::{self, File}, path::Path, time::{SystemTime, UNIX_EPOCH}};
 
-use clap::Args;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
-use rand_distr::{Uniform, Distribution};
+use rand_distr::{Distribution, Normal, Uniform, Zipf};
 
 use crate::instance::TalentSchedInstance;
 
-#[derive(Debug, Args)]
-pub struct TalentSchedGenerator {
-    /// An optional seed to kickstart the instance generation
-    #[clap(short='s', long)]
-    seed: Option<u128>,
+/// The distribution of the daily costs of the actors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CostDistribution {
+    /// Every cost of the range is equally likely
+    Uniform,
+    /// A bell curve, rounded and clamped to the range
+    Normal,
+    /// Many cheap extras and a few expensive stars: the cheapest cost is the
+    /// most likely and each cost is less likely than the previous one
+    Zipf,
+}
+
+/// The parameters of the instance generation
+#[derive(Debug, Clone, Args, Serialize)]
+pub struct GeneratorParams {
+    /// The number of scenes of the instance
     #[clap(short='n', long, default_value="20")]
-    nb_scenes: usize,
+    pub nb_scenes: usize,
     #[clap(short='a', long, default_value="5")]
-    nb_actors: usize,
-    /// The number of clusters of similar scene types
+    pub nb_actors: usize,
+    /// The number of clusters of similar scene types, the scenes are split as
+    /// evenly as possible among them (at most one cluster per scene)
     #[clap(short='c', long, default_value="10")]
-    nb_clusters: usize,
+    pub nb_clusters: usize,
     #[clap(long, default_value="1")]
-    min_cost: usize,
+    pub min_cost: usize,
     #[clap(long, default_value="100")]
-    max_cost: usize,
+    pub max_cost: usize,
+    /// The distribution of the daily costs of the actors between min-cost and max-cost
+    #[clap(long, value_enum, default_value="uniform")]
+    pub cost_dist: CostDistribution,
+    /// The mean of the normal cost distribution, the middle of the range by default
+    #[clap(long)]
+    pub cost_mean: Option<f64>,
+    /// The standard deviation of the normal cost distribution, a sixth of the range by default
+    #[clap(long)]
+    pub cost_stddev: Option<f64>,
+    /// The exponent of the zipf cost distribution, larger values give fewer expensive actors
+    #[clap(long, default_value="1.0")]
+    pub cost_zipf_exponent: f64,
     #[clap(long, default_value="1")]
-    min_duration: usize,
+    pub min_duration: usize,
     #[clap(long, default_value="10")]
-    max_duration: usize,
+    pub max_duration: usize,
     /// The probability of needing an actor for a given scene
     #[clap(short='d', long, default_value="0.5")]
-    density: f64,
+    pub density: f64,
     #[clap(long, default_value="0.7")]
-    similarity: f64,
-    /// Name of the file where to generate the talentsched instance
-    #[clap(short, long)]
+    pub similarity: f64,
+    /// The minimum number of actors needed for each scene, random actors are
+    /// added to the scenes that need fewer after sampling
+    #[clap(long, default_value="1")]
+    pub min_actors_per_scene: usize,
+    /// If present, each actor that is needed for no scene is added to a random
+    /// scene, which slightly increases the density
+    #[clap(long)]
+    pub ensure_actor_usage: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TalentSchedGenerator {
+    /// An optional seed to kickstart the instance generation
+    #[clap(short='s', long)]
+    seed: Option<u128>,
+    #[command(flatten)]
+    params: GeneratorParams,
+    /// Name of the file where to generate the talentsched instance, in bincode if it ends with .bin
+    /// and gzip-compressed if it ends with .gz,
+    /// the seed and the parameters are written next to it in a .meta.json file
+    #[clap(short, long, conflicts_with="output_dir")]
     output: Option<String>,
+    /// Name of the directory where to generate a batch of instances, along with
+    /// a manifest.json listing the seed and the parameters of each of them
+    #[clap(long)]
+    output_dir: Option<String>,
+    /// The number of instances generated in the output directory, the i-th of
+    /// them being generated with the seed incremented by i (1 by default)
+    #[clap(long, requires="output_dir")]
+    count: Option<usize>,
+    /// The explicit seeds of the instances generated in the output directory,
+    /// separated by commas, each instance being named after its seed
+    #[clap(long, value_delimiter=',', requires="output_dir", conflicts_with_all=["seed", "seed_file"])]
+    seed_list: Vec<u128>,
+    /// The path to a file listing the seeds like --seed-list, separated by
+    /// commas or whitespaces
+    #[clap(long, requires="output_dir", conflicts_with="seed")]
+    seed_file: Option<String>,
+}
+
+/// The metadata needed to generate an instance again
+#[derive(Debug, Serialize)]
+struct Metadata<'a> {
+    seed: u128,
+    params: &'a GeneratorParams,
+    version: &'static str,
+}
+
+/// An entry of the manifest of a batch of generated instances
+#[derive(Debug, Serialize)]
+struct ManifestEntry<'a> {
+    file: String,
+    seed: u128,
+    params: &'a GeneratorParams,
 }
 
 impl TalentSchedGenerator {
 
-    pub fn generate(&mut self) {
-        let mut rng = self.rng();
+    pub fn generate(&self) -> Result<(), String> {
+        let seed = self.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis());
 
-        let mut nb_scenes_per_cluster = vec![self.nb_scenes / self.nb_clusters; self.nb_clusters];
-        for i in 0..(self.nb_scenes % self.nb_clusters) {
-            nb_scenes_per_cluster[i] += 1;
+        if let Some(output_dir) = self.output_dir.as_ref() {
+            let seeds = self.seeds(seed)?;
+            return self.generate_batch(Path::new(output_dir), &seeds);
         }
-        
-        let cost = self.generate_costs(&mut rng);
-        let duration = self.generate_duration(&mut rng);
-        let actors = self.generate_actors(&mut rng, &nb_scenes_per_cluster);
 
-        let instance = TalentSchedInstance {
-            nb_scenes: self.nb_scenes,
-            nb_actors: self.nb_actors,
-            cost,
-            duration,
-            actors,
-        };
-
-        let instance = serde_json::to_string_pretty(&instance).unwrap();
+        let instance = generate_instance(&self.params, seed);
 
         if let Some(output) = self.output.as_ref() {
-            File::create(output).unwrap().write_all(instance.as_bytes()).unwrap();
+            instance.try_write(output)?;
+
+            let meta = Metadata { seed, params: &self.params, version: env!("CARGO_PKG_VERSION") };
+            let path = Path::new(output.strip_suffix(".gz").unwrap_or(output)).with_extension("meta.json");
+            let file = File::create(&path).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+            serde_json::to_writer_pretty(file, &meta).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
         } else {
-            println!("{instance}");
+            println!("{}", serde_json::to_string_pretty(&instance).unwrap());
         }
+        Ok(())
     }
 
-    fn generate_costs(&self, rng: &mut impl Rng) -> Vec<usize> {
-        let mut costs = vec![];
+    /// Returns the seed and the name of each instance of the batch: either
+    /// the explicit seeds, or `count` seeds derived from the base seed
+    fn seeds(&self, seed: u128) -> Result<Vec<(u128, String)>, String> {
+        let mut seeds = self.seed_list.clone();
+        if let Some(path) = self.seed_file.as_ref() {
+            let text = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+            for token in text.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()) {
+                seeds.push(token.parse::<u128>().map_err(|_| format!("invalid seed {token} in {path}"))?);
+            }
+        }
 
-        let rand_cost = Uniform::new_inclusive(self.min_cost, self.max_cost);
-        for _ in 0..self.nb_actors {
-            costs.push(rand_cost.sample(rng));
+        if seeds.is_empty() {
+            let count = self.count.unwrap_or(1);
+            return Ok((0..count).map(|i| (seed.wrapping_add(i as u128), format!("instance_{i}.json"))).collect());
         }
 
-        costs
+        if let Some(count) = self.count.filter(|c| *c != seeds.len()) {
+            return Err(format!("--count is {count} but {} seeds were given", seeds.len()));
+        }
+        Ok(seeds.into_iter().map(|seed| (seed, format!("instance_{seed}.json"))).collect())
     }
 
-    fn generate_duration(&self, rng: &mut impl Rng) -> Vec<usize> {
-        let mut durations = vec![];
+    fn generate_batch(&self, output_dir: &Path, seeds: &[(u128, String)]) -> Result<(), String> {
+        fs::create_dir_all(output_dir).map_err(|e| format!("cannot create {}: {e}", output_dir.display()))?;
 
-        let rand_duration = Uniform::new_inclusive(self.min_duration, self.max_duration);
-        for _ in 0..self.nb_scenes {
-            durations.push(rand_duration.sample(rng));
+        let mut manifest = vec![];
+        for (seed, file) in seeds.iter().cloned() {
+            generate_instance(&self.params, seed).try_write(&output_dir.join(&file).to_string_lossy())?;
+            manifest.push(ManifestEntry { file, seed, params: &self.params });
         }
 
-        durations
+        let path = output_dir.join("manifest.json");
+        let file = File::create(&path).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        serde_json::to_writer_pretty(file, &manifest).map_err(|e| format!("cannot write {}: {e}", path.display()))
+    }
+
+}
+
+/// Generates a TalentSched instance with the given parameters, the same seed
+/// always yields the same instance
+pub fn generate_instance(params: &GeneratorParams, seed: u128) -> TalentSchedInstance {
+    let mut rng = rng(seed);
+
+    let nb_clusters = params.nb_clusters.clamp(1, params.nb_scenes.max(1));
+    let mut nb_scenes_per_cluster = vec![params.nb_scenes / nb_clusters; nb_clusters];
+    for i in 0..(params.nb_scenes % nb_clusters) {
+        nb_scenes_per_cluster[i] += 1;
     }
+    
+    let cost = generate_costs(params, &mut rng);
+    let duration = generate_duration(params, &mut rng);
+    let actors = generate_actors(params, &mut rng, &nb_scenes_per_cluster);
+
+    let instance = TalentSchedInstance {
+        nb_scenes: params.nb_scenes,
+        nb_actors: params.nb_actors,
+        cost,
+        duration,
+        actors,
+        contiguous: vec![],
+    };
+    debug_assert!(instance.check_dimensions().is_ok(), "generated an inconsistent instance: {:?}", instance.check_dimensions());
+
+    instance
+}
+
+fn generate_costs(params: &GeneratorParams, rng: &mut impl Rng) -> Vec<usize> {
+    let mut costs = vec![];
 
-    fn generate_actors(&self, rng: &mut impl Rng, nb_scenes_per_cluster: &Vec<usize>) -> Vec<Vec<usize>> {
-        let mut actors = vec![vec![0; self.nb_scenes]; self.nb_actors];
+    let (min, max) = (params.min_cost, params.max_cost.max(params.min_cost));
+    let clamp = |cost: f64| (cost.round().max(0.0) as usize).clamp(min, max);
 
-        let rand = Uniform::new_inclusive(0.0, 1.0);
+    match params.cost_dist {
+        CostDistribution::Uniform => {
+            let rand_cost = Uniform::new_inclusive(min, max);
+            for _ in 0..params.nb_actors {
+                costs.push(rand_cost.sample(rng));
+            }
+        },
+        CostDistribution::Normal => {
+            let mean = params.cost_mean.unwrap_or((min + max) as f64 / 2.0);
+            let stddev = params.cost_stddev.unwrap_or((max - min) as f64 / 6.0).max(0.0);
+            let rand_cost = Normal::new(mean, stddev).unwrap();
+            for _ in 0..params.nb_actors {
+                costs.push(clamp(rand_cost.sample(rng)));
+            }
+        },
+        CostDistribution::Zipf => {
+            // the ranks 1..=n are mapped to the costs min..=max
+            let rand_rank = Zipf::new((max - min + 1) as u64, params.cost_zipf_exponent.max(0.0)).unwrap();
+            for _ in 0..params.nb_actors {
+                costs.push(clamp(min as f64 + rand_rank.sample(rng) - 1.0));
+            }
+        },
+    }
 
-        let mut scene = 0;
-        for nb_scenes in nb_scenes_per_cluster {
-            for i in 0..self.nb_actors {
-                actors[i][scene] = if rand.sample(rng) < self.density {
-                    1
+    costs
+}
+
+fn generate_duration(params: &GeneratorParams, rng: &mut impl Rng) -> Vec<usize> {
+    let mut durations = vec![];
+
+    let rand_duration = Uniform::new_inclusive(params.min_duration, params.max_duration);
+    for _ in 0..params.nb_scenes {
+        durations.push(rand_duration.sample(rng));
+    }
+
+    durations
+}
+
+fn generate_actors(params: &GeneratorParams, rng: &mut impl Rng, nb_scenes_per_cluster: &Vec<usize>) -> Vec<Vec<usize>> {
+    let mut actors = vec![vec![0; params.nb_scenes]; params.nb_actors];
+
+    let rand = Uniform::new_inclusive(0.0, 1.0);
+
+    let mut scene = 0;
+    for nb_scenes in nb_scenes_per_cluster.iter().filter(|n| **n > 0) {
+        for i in 0..params.nb_actors {
+            actors[i][scene] = if rand.sample(rng) < params.density {
+                1
+            } else {
+                0
+            };
+        }
+
+        for j in 1..*nb_scenes {
+            for i in 0..params.nb_actors {
+                actors[i][scene+j] = if rand.sample(rng) < params.similarity {
+                    actors[i][scene]
                 } else {
-                    0
+                    1 - actors[i][scene]
                 };
             }
+        }
 
-            for j in 1..*nb_scenes {
-                for i in 0..self.nb_actors {
-                    actors[i][scene+j] = if rand.sample(rng) < self.similarity {
-                        actors[i][scene]
-                    } else {
-                        1 - actors[i][scene]
-                    };
-                }
-            }
+        scene += *nb_scenes;
+    }
+
+    let min_actors = params.min_actors_per_scene.min(params.nb_actors);
+    for scene in 0..params.nb_scenes {
+        let mut absent = (0..params.nb_actors).filter(|i| actors[*i][scene] == 0).collect::<Vec<usize>>();
+        while params.nb_actors - absent.len() < min_actors {
+            let actor = absent.swap_remove(rng.gen_range(0..absent.len()));
+            actors[actor][scene] = 1;
+        }
+    }
 
-            scene += *nb_scenes;
+    if params.ensure_actor_usage && params.nb_scenes > 0 {
+        for row in actors.iter_mut().filter(|row| row.iter().all(|x| *x == 0)) {
+            row[rng.gen_range(0..params.nb_scenes)] = 1;
         }
+    }
+
+    actors
+}
+
+fn rng(init: u128) -> impl Rng {
+    let mut seed = [0_u8; 32];
+    seed.iter_mut().zip(init.to_be_bytes().into_iter()).for_each(|(s, i)| *s = i);
+    seed.iter_mut().rev().zip(init.to_le_bytes().into_iter()).for_each(|(s, i)| *s = i);
+    ChaChaRng::from_seed(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(nb_scenes: usize, nb_clusters: usize) -> GeneratorParams {
+        GeneratorParams {
+            nb_scenes,
+            nb_actors: 5,
+            nb_clusters,
+            min_cost: 1,
+            max_cost: 100,
+            cost_dist: CostDistribution::Uniform,
+            cost_mean: None,
+            cost_stddev: None,
+            cost_zipf_exponent: 1.0,
+            min_duration: 1,
+            max_duration: 10,
+            density: 0.5,
+            similarity: 0.7,
+            min_actors_per_scene: 1,
+            ensure_actor_usage: false,
+        }
+    }
 
-        actors
+    fn assert_scene_count(params: &GeneratorParams) {
+        for seed in 0..10 {
+            let instance = generate_instance(params, seed);
+            assert_eq!(instance.nb_scenes, params.nb_scenes);
+            assert_eq!(instance.duration.len(), params.nb_scenes);
+            assert!(instance.actors.iter().all(|a| a.len() == params.nb_scenes));
+            assert!(instance.check_dimensions().is_ok(), "{:?}", instance.check_dimensions());
+        }
+    }
+
+    #[test]
+    fn more_clusters_than_scenes() {
+        assert_scene_count(&params(4, 10));
+        assert_scene_count(&params(1, 2));
     }
 
-    fn rng(&self) -> impl Rng {
-        let init = self.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis());
-        let mut seed = [0_u8; 32];
-        seed.iter_mut().zip(init.to_be_bytes().into_iter()).for_each(|(s, i)| *s = i);
-        seed.iter_mut().rev().zip(init.to_le_bytes().into_iter()).for_each(|(s, i)| *s = i);
-        ChaChaRng::from_seed(seed)
+    #[test]
+    fn zero_clusters() {
+        assert_scene_count(&params(6, 0));
+        assert_scene_count(&params(0, 0));
     }
 
+    #[test]
+    fn scenes_of_a_cluster_share_more_actors() {
+        let params = GeneratorParams { nb_actors: 20, similarity: 0.95, ..params(22, 4) };
+        // the first two clusters get one extra scene
+        let cluster = |scene: usize| [6, 6, 5, 5].iter()
+            .scan(0, |end, n| { *end += n; Some(*end) })
+            .position(|end| scene < end)
+            .unwrap();
+
+        let (mut within, mut across) = ((0.0, 0), (0.0, 0));
+        for seed in 0..10 {
+            let instance = generate_instance(&params, seed);
+            for a in 0..params.nb_scenes {
+                for b in (a + 1)..params.nb_scenes {
+                    let agreement = (0..params.nb_actors)
+                        .filter(|i| instance.actors[*i][a] == instance.actors[*i][b])
+                        .count() as f64 / params.nb_actors as f64;
+                    let total = if cluster(a) == cluster(b) { &mut within } else { &mut across };
+                    total.0 += agreement;
+                    total.1 += 1;
+                }
+            }
+        }
+
+        let (within, across) = (within.0 / within.1 as f64, across.0 / across.1 as f64);
+        assert!(within > across + 0.2, "agreement {within} within clusters and {across} across clusters");
+    }
 }
@@ -0,0 +1,73 @@
+//! This module prints a summary of an instance, along with the empirical
+//! values of the generator parameters that shaped it.
+
+use clap::Args;
+
+use crate::instance::{TalentSchedInstance, UNKNOWN};
+
+#[derive(Debug, Args)]
+pub struct Info {
+    /// The path to the instance file
+    #[clap(short, long)]
+    pub instance: String,
+}
+
+impl Info {
+    pub fn info(&self) -> Result<(), String> {
+        let instance = TalentSchedInstance::try_read(&self.instance)?;
+        instance.check_dimensions().map_err(|e| format!("invalid instance: {e}"))?;
+
+        println!("scenes {}", instance.nb_scenes);
+        println!("actors {}", instance.nb_actors);
+        println!("total duration {}", instance.duration.iter().sum::<usize>());
+        if let (Some(min), Some(max)) = (instance.cost.iter().min(), instance.cost.iter().max()) {
+            println!("cost range {min}..={max}");
+        }
+        println!("density {:.3}", density(&instance));
+        let unknown = instance.unknown_entries();
+        if unknown > 0 {
+            println!("unknown entries {unknown}");
+        }
+
+        if let Some(agreement) = adjacent_agreement(&instance) {
+            println!("adjacent agreement {agreement:.3}");
+            println!("estimated similarity {:.3}", estimate_similarity(agreement));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the fraction of the known entries of the actor matrix that are
+/// set, i.e. the empirical counterpart of the generator's density
+pub fn density(instance: &TalentSchedInstance) -> f64 {
+    let cells = instance.nb_scenes * instance.nb_actors - instance.unknown_entries();
+    if cells == 0 {
+        return 0.0;
+    }
+
+    let ones = instance.actors.iter().map(|a| a.iter().filter(|x| **x != 0 && **x != UNKNOWN).count()).sum::<usize>();
+    ones as f64 / cells as f64
+}
+
+/// Returns the fraction of actors whose presence is the same in two
+/// consecutive scenes, or None when there are less than two scenes
+pub fn adjacent_agreement(instance: &TalentSchedInstance) -> Option<f64> {
+    if instance.nb_scenes < 2 || instance.nb_actors == 0 {
+        return None;
+    }
+
+    let mut same = 0;
+    for actor in instance.actors.iter() {
+        same += actor.windows(2).filter(|w| (w[0] != 0) == (w[1] != 0)).count();
+    }
+
+    Some(same as f64 / ((instance.nb_scenes - 1) * instance.nb_actors) as f64)
+}
+
+/// Estimates the generator's similarity from the adjacent agreement. Two
+/// scenes of a cluster both copy or flip each entry of the first scene of the
+/// cluster, so they agree with probability s^2 + (1-s)^2. This ignores the
+/// boundaries between clusters, so it is only accurate when clusters are large.
+pub fn estimate_similarity(agreement: f64) -> f64 {
+    (1.0 + (2.0 * agreement - 1.0).max(0.0).sqrt()) / 2.0
+}
@@ -1,6 +1,18 @@
 //! This module defines an abstract representation of a TalentSched instance.
 
-use serde::{Serialize, Deserialize};
+use std::{fmt, fs::File, io::{BufReader, BufWriter, Read, Write}, path::Path};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+/// The value of an entry of the actor matrix when it is not known whether the
+/// actor plays in the scene, written as `null` in JSON
+pub const UNKNOWN: usize = usize::MAX;
+
+/// The largest number of scenes or actors supported by the solver, whose
+/// states are sets of 64 bits
+pub const MAX_SIZE: usize = 64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TalentSchedInstance {
@@ -8,5 +20,363 @@ pub struct TalentSchedInstance {
     pub nb_actors: usize,
     pub cost: Vec<usize>,
     pub duration: Vec<usize>,
+    /// For each actor and each scene, 1 if the actor plays in the scene, 0 if
+    /// they do not, and `UNKNOWN` (null in JSON) if it is not known yet
+    #[serde(serialize_with = "serialize_actors", deserialize_with = "deserialize_actors")]
     pub actors: Vec<Vec<usize>>,
+    /// For each actor, whether all their scenes must be shot in a single block
+    /// without any day on location where they do not play. Can be left empty
+    /// when no actor has such a contract.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contiguous: Vec<bool>,
+}
+
+fn serialize_actors<S: Serializer>(actors: &[Vec<usize>], serializer: S) -> Result<S::Ok, S::Error> {
+    actors.iter()
+        .map(|a| a.iter().map(|x| if *x == UNKNOWN { None } else { Some(*x) }).collect::<Vec<Option<usize>>>())
+        .collect::<Vec<Vec<Option<usize>>>>()
+        .serialize(serializer)
+}
+
+fn deserialize_actors<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<usize>>, D::Error> {
+    let actors = Vec::<Vec<Option<usize>>>::deserialize(deserializer)?;
+    Ok(actors.into_iter().map(|a| a.into_iter().map(|x| x.unwrap_or(UNKNOWN)).collect()).collect())
+}
+
+/// A constraint of the instance that is not satisfied by a schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The scene does not exist in the instance
+    UnknownScene(usize),
+    /// The scene is shot more than once
+    DuplicateScene(usize),
+    /// The scene is never shot
+    MissingScene(usize),
+    /// The actor must play in a single block but is on location without
+    /// playing in the scene at the given position
+    NotContiguous { actor: usize, position: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownScene(scene) => write!(f, "scene {scene} does not exist"),
+            Violation::DuplicateScene(scene) => write!(f, "scene {scene} is shot more than once"),
+            Violation::MissingScene(scene) => write!(f, "scene {scene} is never shot"),
+            Violation::NotContiguous { actor, position } =>
+                write!(f, "actor {actor} must play in a single block but does not play at position {position}"),
+        }
+    }
+}
+
+/// The binary representation of an instance. Bincode is not self-describing,
+/// so all the fields are always written, even those that are skipped in JSON.
+#[derive(Serialize, Deserialize)]
+struct BinaryInstance {
+    nb_scenes: usize,
+    nb_actors: usize,
+    cost: Vec<usize>,
+    duration: Vec<usize>,
+    actors: Vec<Vec<usize>>,
+    contiguous: Vec<bool>,
+}
+
+impl From<BinaryInstance> for TalentSchedInstance {
+    fn from(b: BinaryInstance) -> Self {
+        let BinaryInstance { nb_scenes, nb_actors, cost, duration, actors, contiguous } = b;
+        TalentSchedInstance { nb_scenes, nb_actors, cost, duration, actors, contiguous }
+    }
+}
+
+impl From<TalentSchedInstance> for BinaryInstance {
+    fn from(i: TalentSchedInstance) -> Self {
+        let TalentSchedInstance { nb_scenes, nb_actors, cost, duration, actors, contiguous } = i;
+        BinaryInstance { nb_scenes, nb_actors, cost, duration, actors, contiguous }
+    }
+}
+
+/// Instances stored in files with this extension use bincode instead of JSON,
+/// possibly followed by `.gz`
+fn is_binary(path: &str) -> bool {
+    Path::new(path.strip_suffix(".gz").unwrap_or(path)).extension().map(|ext| ext == "bin").unwrap_or(false)
+}
+
+/// Instances stored in files with this extension are gzip-compressed
+fn is_gzip(path: &str) -> bool {
+    Path::new(path).extension().map(|ext| ext == "gz").unwrap_or(false)
+}
+
+impl TalentSchedInstance {
+    /// Checks that the sizes of the vectors match the number of scenes and of
+    /// actors, and that these numbers fit in the sets used by the solver
+    pub fn validate(&self) -> Result<(), String> {
+        if self.nb_scenes > MAX_SIZE || self.nb_actors > MAX_SIZE {
+            return Err(format!("the instance has {} scenes and {} actors, at most {MAX_SIZE} of each are supported", self.nb_scenes, self.nb_actors));
+        }
+        self.check_dimensions()
+    }
+
+    /// Checks that the sizes of the vectors match the number of scenes and of
+    /// actors, whatever these numbers are
+    pub fn check_dimensions(&self) -> Result<(), String> {
+        if self.cost.len() != self.nb_actors {
+            return Err(format!("cost has {} entries but there are {} actors", self.cost.len(), self.nb_actors));
+        }
+        if self.duration.len() != self.nb_scenes {
+            return Err(format!("duration has {} entries but there are {} scenes", self.duration.len(), self.nb_scenes));
+        }
+        if self.actors.len() != self.nb_actors {
+            return Err(format!("actors has {} rows but there are {} actors", self.actors.len(), self.nb_actors));
+        }
+        if let Some((actor, row)) = self.actors.iter().enumerate().find(|(_, row)| row.len() != self.nb_scenes) {
+            return Err(format!("the row of actor {actor} has {} entries but there are {} scenes", row.len(), self.nb_scenes));
+        }
+        if self.contiguous.len() > self.nb_actors {
+            return Err(format!("contiguous has {} entries but there are {} actors", self.contiguous.len(), self.nb_actors));
+        }
+        Ok(())
+    }
+
+    /// Returns whether the actor plays in the scene. The entries of the actor
+    /// matrix should be 0 or 1, any other nonzero value is treated as 1. The
+    /// unknown entries are also treated as 1, i.e. pessimistically, unless
+    /// they were resolved with `resolve_unknown` beforehand.
+    pub fn plays(&self, actor: usize, scene: usize) -> bool {
+        self.actors[actor][scene] != 0
+    }
+
+    /// Returns the number of entries of the actor matrix that are neither 0,
+    /// 1 nor unknown
+    pub fn non_binary_entries(&self) -> usize {
+        self.actors.iter().map(|a| a.iter().filter(|x| **x > 1 && **x != UNKNOWN).count()).sum()
+    }
+
+    /// Replaces the nonzero entries of the actor matrix by 1, except the
+    /// unknown ones
+    pub fn normalize(&mut self) {
+        self.actors.iter_mut().flatten().filter(|x| **x > 1 && **x != UNKNOWN).for_each(|x| *x = 1);
+    }
+
+    /// Returns the number of unknown entries of the actor matrix
+    pub fn unknown_entries(&self) -> usize {
+        self.actors.iter().map(|a| a.iter().filter(|x| **x == UNKNOWN).count()).sum()
+    }
+
+    /// Replaces the unknown entries of the actor matrix by 1 if `present` is
+    /// true and by 0 otherwise
+    pub fn resolve_unknown(&mut self, present: bool) {
+        let value = if present { 1 } else { 0 };
+        self.actors.iter_mut().flatten().filter(|x| **x == UNKNOWN).for_each(|x| *x = value);
+    }
+
+    /// Reads an instance from the given file, in bincode if its extension is
+    /// `.bin` and in JSON otherwise, decompressing it first if it ends with `.gz`
+    pub fn read(path: &str) -> Self {
+        Self::try_read(path).unwrap()
+    }
+
+    /// Reads an instance from the given file like `read`, but reports a
+    /// missing or malformed file as an error
+    pub fn try_read(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("cannot open {path}: {e}"))?;
+        let reader: Box<dyn Read> = if is_gzip(path) {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(BufReader::new(file))
+        };
+        if is_binary(path) {
+            bincode::deserialize_from::<_, BinaryInstance>(reader)
+                .map(|b| b.into())
+                .map_err(|e| format!("invalid instance in {path}: {e}"))
+        } else {
+            serde_json::from_reader(reader).map_err(|e| format!("invalid instance in {path}: {e}"))
+        }
+    }
+
+    /// Writes the instance to the given file, in bincode if its extension is
+    /// `.bin` and in pretty-printed JSON otherwise, compressed if it ends with `.gz`
+    pub fn write(&self, path: &str) {
+        self.try_write(path).unwrap()
+    }
+
+    /// Writes the instance to the given file like `write`, but reports the
+    /// failure to create or to write the file as an error
+    pub fn try_write(&self, path: &str) -> Result<(), String> {
+        let error = |e: &dyn fmt::Display| format!("cannot write {path}: {e}");
+        let mut writer = BufWriter::new(File::create(path).map_err(|e| error(&e))?);
+        if is_gzip(path) {
+            let mut encoder = GzEncoder::new(writer, Compression::default());
+            self.write_to(&mut encoder, is_binary(path)).map_err(|e| error(&e))?;
+            encoder.finish().and_then(|mut w| w.flush()).map_err(|e| error(&e))
+        } else {
+            self.write_to(&mut writer, is_binary(path)).map_err(|e| error(&e))?;
+            writer.flush().map_err(|e| error(&e))
+        }
+    }
+
+    fn write_to(&self, writer: impl Write, binary: bool) -> Result<(), String> {
+        if binary {
+            bincode::serialize_into(writer, &BinaryInstance::from(self.clone())).map_err(|e| e.to_string())
+        } else {
+            serde_json::to_writer_pretty(writer, self).map_err(|e| e.to_string())
+        }
+    }
+
+    /// Computes the total cost of shooting the scenes in the given order: each
+    /// actor is paid from the day of their first scene until the day of their
+    /// last scene.
+    pub fn schedule_cost(&self, order: &[usize]) -> usize {
+        self.schedule_cost_with(order, &self.cost)
+    }
+
+    /// Computes the total cost of shooting the scenes in the given order when
+    /// each actor is paid the given daily cost
+    pub fn schedule_cost_with(&self, order: &[usize], daily_cost: &[usize]) -> usize {
+        let mut cost = 0;
+
+        for actor in 0..self.nb_actors {
+            let first = order.iter().position(|s| self.plays(actor, *s));
+            let last = order.iter().rposition(|s| self.plays(actor, *s));
+
+            if let (Some(first), Some(last)) = (first, last) {
+                let days = order[first..=last].iter().map(|s| self.duration[*s]).sum::<usize>();
+                cost += daily_cost[actor] * days;
+            }
+        }
+
+        cost
+    }
+
+    /// Returns the cost of the days where the actors play, which is a lower
+    /// bound on the cost of any schedule since each actor is at least paid
+    /// for their own scenes. The unknown entries are not counted, so the bound
+    /// holds whatever they turn out to be.
+    pub fn appearance_cost(&self) -> usize {
+        self.appearance_cost_with(&self.cost)
+    }
+
+    /// Returns the cost of the days where the actors play when each actor is
+    /// paid the given daily cost
+    pub fn appearance_cost_with(&self, daily_cost: &[usize]) -> usize {
+        let mut cost = 0;
+        for (actor, scenes) in self.actors.iter().enumerate() {
+            for (scene, _) in scenes.iter().enumerate().filter(|(_, x)| **x != 0 && **x != UNKNOWN) {
+                cost += daily_cost[actor] * self.duration[scene];
+            }
+        }
+        cost
+    }
+
+    /// Returns the position of the last scene of the given actor in the order,
+    /// i.e. the position after which the actor is released
+    pub fn release_position(&self, order: &[usize], actor: usize) -> Option<usize> {
+        order.iter().rposition(|s| self.plays(actor, *s))
+    }
+
+    /// Returns the constraints of the instance that are not satisfied by the
+    /// given order: it must shoot each scene exactly once, and the actors with
+    /// a contiguity constraint must play in every scene between their first
+    /// and their last one
+    pub fn violations(&self, order: &[usize]) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        let mut seen = vec![false; self.nb_scenes];
+        for scene in order.iter().copied() {
+            if scene >= self.nb_scenes {
+                violations.push(Violation::UnknownScene(scene));
+            } else if seen[scene] {
+                violations.push(Violation::DuplicateScene(scene));
+            } else {
+                seen[scene] = true;
+            }
+        }
+        for (scene, _) in seen.iter().enumerate().filter(|(_, s)| !**s) {
+            violations.push(Violation::MissingScene(scene));
+        }
+
+        // the contiguity check needs valid scene indices
+        if violations.iter().any(|v| matches!(v, Violation::UnknownScene(_))) {
+            return violations;
+        }
+
+        for (actor, _) in self.contiguous.iter().enumerate().filter(|(_, c)| **c) {
+            let first = order.iter().position(|s| self.plays(actor, *s));
+            let last = order.iter().rposition(|s| self.plays(actor, *s));
+
+            if let (Some(first), Some(last)) = (first, last) {
+                for position in first..=last {
+                    if !self.plays(actor, order[position]) {
+                        violations.push(Violation::NotContiguous { actor, position });
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    fn instance() -> TalentSchedInstance {
+        TalentSchedInstance {
+            nb_scenes: 3,
+            nb_actors: 2,
+            cost: vec![5, 7],
+            duration: vec![1, 2, 3],
+            actors: vec![vec![1, UNKNOWN, 0], vec![0, 1, UNKNOWN]],
+            contiguous: vec![false, true],
+        }
+    }
+
+    /// Returns a path in the temporary directory that no other test uses
+    fn temp_path(name: &str) -> String {
+        env::temp_dir().join(format!("talentsched-{}-{name}", process::id())).to_string_lossy().to_string()
+    }
+
+    fn same(a: &TalentSchedInstance, b: &TalentSchedInstance) -> bool {
+        serde_json::to_value(a).unwrap() == serde_json::to_value(b).unwrap()
+    }
+
+    #[test]
+    fn json_bincode_json_round_trip() {
+        let original = instance();
+        let mut current = original.clone();
+        for name in ["round-trip.json", "round-trip.bin", "round-trip.bin.gz", "round-trip.json.gz", "round-trip-again.json"] {
+            let path = temp_path(name);
+            current.write(&path);
+            current = TalentSchedInstance::try_read(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+
+            assert!(same(&original, &current), "{name}: {current:?}");
+        }
+        assert_eq!(current.actors[0][1], UNKNOWN);
+        assert_eq!(current.contiguous, vec![false, true]);
+    }
+
+    #[test]
+    fn normalize_keeps_binary_and_unknown_entries() {
+        let mut instance = instance();
+        instance.actors = vec![vec![2, UNKNOWN, 0], vec![1, 7, usize::MAX - 1]];
+        assert_eq!(instance.non_binary_entries(), 3);
+        assert!(instance.plays(0, 0) && instance.plays(1, 1));
+
+        instance.normalize();
+        assert_eq!(instance.non_binary_entries(), 0);
+        assert_eq!(instance.actors, vec![vec![1, UNKNOWN, 0], vec![1, 1, 1]]);
+        assert_eq!(instance.unknown_entries(), 1);
+    }
+
+    #[test]
+    fn appearance_cost_ignores_unknown_entries() {
+        let mut instance = instance();
+        assert_eq!(instance.appearance_cost(), 5 + 7 * 2);
+
+        instance.resolve_unknown(true);
+        assert_eq!(instance.appearance_cost(), 5 + 5 * 2 + 7 * 2 + 7 * 3);
+    }
 }
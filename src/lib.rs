@@ -0,0 +1,20 @@
+//! This crate provides tools to generate and solve TalentSched instances
+//! with decision diagrams.
+
+pub mod instance;
+pub mod format;
+pub mod generate;
+pub mod resolution;
+pub mod solution;
+pub mod source;
+pub mod diff;
+pub mod convert;
+pub mod info;
+pub mod lowerbound;
+pub mod verify;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "cffi")]
+pub mod ffi;
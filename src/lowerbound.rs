@@ -0,0 +1,22 @@
+//! This module computes the trivial lower bound of an instance without any
+//! search: each actor is at least paid for the days where they play.
+
+use clap::Args;
+
+use crate::instance::TalentSchedInstance;
+
+#[derive(Debug, Args)]
+pub struct LowerBound {
+    /// The path to the instance file
+    #[clap(short, long)]
+    pub instance: String,
+}
+
+impl LowerBound {
+    pub fn lower_bound(&self) -> Result<(), String> {
+        let instance = TalentSchedInstance::try_read(&self.instance)?;
+        instance.check_dimensions().map_err(|e| format!("invalid instance: {e}"))?;
+        println!("lower bound {}", instance.appearance_cost());
+        Ok(())
+    }
+}
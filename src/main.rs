@@ -1,10 +1,11 @@
 use clap::{Parser, Subcommand};
-use generate::TalentSchedGenerator;
-use resolution::Solve;
-
-mod instance;
-mod generate;
-mod resolution;
+use talentsched::convert::Convert;
+use talentsched::diff::Diff;
+use talentsched::generate::TalentSchedGenerator;
+use talentsched::info::Info;
+use talentsched::lowerbound::LowerBound;
+use talentsched::resolution::{Solve, SolveBatch};
+use talentsched::verify::{Check, Verify};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
@@ -17,13 +18,70 @@ struct TalentSchedTools {
 #[derive(Debug, Subcommand)]
 enum Command {
     Generate(TalentSchedGenerator),
-    Solve(Solve)
+    Solve(Solve),
+    SolveBatch(SolveBatch),
+    Diff(Diff),
+    Info(Info),
+    Verify(Verify),
+    Check(Check),
+    Lowerbound(LowerBound),
+    Convert(Convert),
 }
 
 fn main() {
     let cli = TalentSchedTools::parse();
-    match cli.command {
-        Command::Generate(mut generate) => generate.generate(),
-        Command::Solve(solve) => solve.solve()
-    }
+    let code = match cli.command {
+        Command::Generate(generate) => match generate.generate() {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Solve(solve) => solve.solve().exit_code(),
+        Command::SolveBatch(batch) => batch.solve().exit_code(),
+        Command::Diff(diff) => match diff.diff() {
+            Ok(valid) => if valid { 0 } else { 1 },
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Info(info) => match info.info() {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Verify(verify) => match verify.verify() {
+            Ok(valid) => if valid { 0 } else { 1 },
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Check(check) => match check.check() {
+            Ok(valid) => if valid { 0 } else { 1 },
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Lowerbound(lower_bound) => match lower_bound.lower_bound() {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+        Command::Convert(convert) => match convert.convert() {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("error: {e}");
+                1
+            }
+        },
+    };
+    std::process::exit(code);
 }
@@ -0,0 +1,175 @@
+//! This module solves all the instances of a directory with the same settings.
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use clap::Args;
+
+use crate::instance::TalentSchedInstance;
+use crate::resolution::clock::Instant;
+use crate::resolution::builder::{SolveBuilder, SolveStatus, SolverType};
+use crate::resolution::model::Objective;
+use crate::resolution::report::SolveReport;
+use crate::resolution::table::{Aligned, ResultRow, TableFormat};
+
+/// The suffixes of the files of a directory that are solved as instances
+const INSTANCE_SUFFIXES: [&str; 4] = [".json", ".json.gz", ".bin", ".bin.gz"];
+
+#[derive(Debug, Args)]
+pub struct SolveBatch {
+    /// The directory containing the instances, all its .json, .bin, .json.gz and
+    /// .bin.gz files are solved except manifest.json and the .meta.json files of
+    /// the generator
+    pub dir: String,
+    /// max number of nodes in a layer
+    #[clap(short, long, default_value="100")]
+    pub width: usize,
+    /// timeout in seconds for each instance, 0 means no time limit
+    #[clap(short, long, visible_alias="instance-timeout", default_value="60")]
+    pub timeout: u64,
+    /// If present, the time in seconds allowed for the whole batch, the instances
+    /// that were not started in time are skipped and reported
+    #[clap(long)]
+    pub total_timeout: Option<u64>,
+    /// The number of threads used by the solver, 0 uses all logical cores
+    #[clap(long, default_value="0")]
+    pub threads: usize,
+    /// The algorithm used to solve the instances
+    #[clap(long, value_enum, default_value="barrier")]
+    pub solver: SolverType,
+    /// The objective function that is minimized
+    #[clap(long, value_enum, default_value="weighted-cost")]
+    pub objective: Objective,
+    /// If present, the path of a CSV file where to append one row per instance
+    #[clap(long)]
+    pub csv: Option<String>,
+    /// How the results are printed, the rows of the table are printed as soon
+    /// as each instance is solved and the other formats once all are solved
+    #[clap(long, value_enum, default_value="table")]
+    pub format: TableFormat,
+}
+
+impl SolveBatch {
+    /// Solves each instance of the directory, going on after the failures,
+    /// and returns the worst status among them
+    pub fn solve(&self) -> SolveStatus {
+        let files = match self.files() {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("error: {e}");
+                return SolveStatus::Failed;
+            }
+        };
+
+        let builder = self.builder();
+        let mut statuses = vec![];
+        let mut total_time = 0.0;
+        let mut rows = vec![];
+        let mut skipped = vec![];
+        let start = Instant::now();
+
+        if self.format == TableFormat::Table {
+            println!("{}", Aligned::header());
+        }
+        for file in files.iter() {
+            let path = file.to_string_lossy();
+            let remaining = self.total_timeout.map(|total| Duration::from_secs(total).saturating_sub(start.elapsed()));
+            if remaining.map(|r| r.is_zero()).unwrap_or(false) {
+                eprintln!("skipped {path}: the total timeout was reached");
+                skipped.push(path.to_string());
+                statuses.push(SolveStatus::Failed);
+                continue;
+            }
+            let result = TalentSchedInstance::try_read(&path).and_then(|instance| match remaining {
+                Some(remaining) => builder.clone().cap_timeout(remaining).run(&instance),
+                None => builder.run(&instance),
+            });
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("error: {path}: {e}");
+                    statuses.push(SolveStatus::Failed);
+                    continue;
+                }
+            };
+            total_time += result.duration;
+            statuses.push(result.status());
+
+            if let Some(csv) = self.csv.as_ref() {
+                let report = SolveReport::new(&path, self.solver, self.width, builder.nb_threads(), &result);
+                if let Err(e) = report.append_csv(csv) {
+                    eprintln!("error: {e}");
+                }
+            }
+
+            let row = ResultRow { instance: path.to_string(), result };
+            if self.format == TableFormat::Table {
+                println!("{}", Aligned::row(&row));
+            }
+            rows.push(row);
+        }
+
+        if self.format != TableFormat::Table {
+            println!("{}", self.format.formatter().format(&rows));
+        }
+
+        let count = |status: SolveStatus| statuses.iter().filter(|s| **s == status).count();
+        println!("instances {}", statuses.len());
+        println!("optimal {}", count(SolveStatus::Optimal));
+        println!("feasible {}", count(SolveStatus::Feasible));
+        println!("failed {}", count(SolveStatus::Failed));
+        if !skipped.is_empty() {
+            println!("skipped {} (total timeout): {}", skipped.len(), skipped.join(" "));
+        }
+        println!("total time {total_time:.3}");
+
+        statuses.into_iter().max_by_key(|s| s.exit_code()).unwrap_or(SolveStatus::Optimal)
+    }
+
+    /// Returns the instance files of the directory, sorted by name
+    fn files(&self) -> Result<Vec<PathBuf>, String> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| format!("cannot read {}: {e}", self.dir))?;
+        let mut files = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.file_name().map(|name| is_instance(&name.to_string_lossy())).unwrap_or(false))
+            .collect::<Vec<PathBuf>>();
+        files.sort();
+        Ok(files)
+    }
+
+    fn builder(&self) -> SolveBuilder {
+        let mut builder = SolveBuilder::new()
+            .width(self.width)
+            .without_timeout()
+            .threads(self.threads)
+            .solver(self.solver)
+            .objective(self.objective);
+
+        if self.timeout > 0 {
+            builder = builder.timeout(Duration::from_secs(self.timeout));
+        }
+        builder
+    }
+}
+
+/// Returns whether the file with the given name is an instance, i.e. whether
+/// it is neither a manifest nor the metadata written by the generator
+fn is_instance(name: &str) -> bool {
+    INSTANCE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        && name != "manifest.json"
+        && !name.ends_with(".meta.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_files() {
+        for name in ["a.json", "a.json.gz", "a.bin", "a.bin.gz", "a.b.json"] {
+            assert!(is_instance(name), "{name}");
+        }
+        for name in ["manifest.json", "a.meta.json", "a.gz", "a.csv", "a.talent", "json", "a.json.bak"] {
+            assert!(!is_instance(name), "{name}");
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! This module provides a library API to configure and run a solve without
+//! going through the command line.
+
+use std::{sync::Arc, thread, time::Duration};
+
+use clap::ValueEnum;
+use ddo::{FixedWidth, WidthHeuristic, NoDupFringe, SimpleFringe, MaxUB, ParBarrierSolverFc, Completion, Solver, Cutoff, Fringe, Problem, Relaxation};
+use serde::{Serialize, Deserialize};
+
+use crate::resolution::model::{TalentSched, TalentSchedRelax, TalentSchedRanking, TalentSchedState, Objective, ROUNDING_EPSILON, value_to_cost};
+use crate::resolution::clock::Instant;
+use crate::resolution::cost::CostModel;
+use crate::resolution::cutoff::{AnyCutoff, MemoryLimit, NodeLimit, time_budget};
+use crate::resolution::dfs::DepthFirstSearch;
+use crate::resolution::restricted::restricted_solution;
+use crate::resolution::monitor::{SearchMonitor, MonitoredFringe, StallTimeout};
+use crate::resolution::width::{AdaptiveWidth, LinearWidth, NbVarsWidth, WidthMode};
+use crate::instance::TalentSchedInstance;
+
+/// The kind of fringe used to store the open subproblems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FringeType {
+    /// Merges the subproblems that share the same state (less memory)
+    #[value(name = "nodup")]
+    NoDup,
+    /// Keeps every subproblem that is pushed
+    Simple,
+}
+
+/// The algorithm used to solve the instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SolverType {
+    /// The parallel branch-and-bound with barrier of ddo
+    Barrier,
+    /// A depth-first branch-and-bound using little memory
+    Dfs,
+}
+
+/// The outcome of a solve, which determines the exit code of the process:
+/// - 0 when the best solution is proven optimal,
+/// - 3 when a solution was found but not proven optimal (e.g. timeout),
+/// - 4 when no solution was found or the run failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveStatus {
+    Optimal,
+    Feasible,
+    Failed,
+}
+
+impl SolveStatus {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SolveStatus::Optimal => 0,
+            SolveStatus::Feasible => 3,
+            SolveStatus::Failed => 4,
+        }
+    }
+}
+
+/// The result of a solve, where the values are expressed as costs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolveResult {
+    /// Whether the best solution is proven optimal
+    pub is_exact: bool,
+    /// The cost of the best solution, if any was found
+    pub best_value: Option<isize>,
+    /// A lower bound on the optimal cost
+    pub best_bound: isize,
+    /// The scenes in the order in which they are shot in the best solution
+    pub solution: Vec<usize>,
+    /// The time spent solving, in seconds
+    pub duration: f64,
+    /// The number of nodes expanded during the search
+    pub explored: usize,
+    /// The limit that stopped the search before it was over, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_by: Option<String>,
+}
+
+impl SolveResult {
+    /// Returns the relative gap between the best solution and the bound, if a
+    /// solution was found. It is exactly 0 when the solution is proven optimal.
+    pub fn gap(&self) -> Option<f64> {
+        self.best_value.map(|best| {
+            if self.is_exact {
+                0.0
+            } else {
+                (best - self.best_bound).abs() as f64 / best.max(1) as f64
+            }
+        })
+    }
+
+    pub fn status(&self) -> SolveStatus {
+        if self.best_value.is_none() {
+            SolveStatus::Failed
+        } else if self.is_exact {
+            SolveStatus::Optimal
+        } else {
+            SolveStatus::Feasible
+        }
+    }
+}
+
+/// Configures and runs a solve, e.g.
+/// `SolveBuilder::new().width(1000).timeout(Duration::from_secs(10)).run(&instance)?`
+///
+/// The time, node and memory limits can be combined freely: the search stops
+/// as soon as the first of them is reached. Without any limit, the search only
+/// stops once optimality is proven.
+#[derive(Clone)]
+pub struct SolveBuilder {
+    width: usize,
+    width_mode: WidthMode,
+    timeout: Option<Duration>,
+    max_nodes: Option<usize>,
+    threads: usize,
+    solver: SolverType,
+    fringe: FringeType,
+    objective: Objective,
+    cost_model: Option<Arc<dyn CostModel>>,
+    deadline: Option<(usize, usize)>,
+    max_memory: Option<usize>,
+    stall_timeout: Option<Duration>,
+    heuristic_only: bool,
+    first_feasible: bool,
+    deterministic: bool,
+    rounding_epsilon: f64,
+    monitor: Option<Arc<SearchMonitor>>,
+}
+
+impl Default for SolveBuilder {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            width_mode: WidthMode::Fixed,
+            timeout: Some(Duration::from_secs(60)),
+            max_nodes: None,
+            threads: 0,
+            solver: SolverType::Barrier,
+            fringe: FringeType::NoDup,
+            objective: Objective::WeightedCost,
+            cost_model: None,
+            deadline: None,
+            max_memory: None,
+            stall_timeout: None,
+            heuristic_only: false,
+            first_feasible: false,
+            deterministic: false,
+            rounding_epsilon: ROUNDING_EPSILON,
+            monitor: None,
+        }
+    }
+}
+
+impl SolveBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The maximum number of nodes in a layer of the decision diagrams
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// How the width is used for each subproblem
+    pub fn width_mode(mut self, width_mode: WidthMode) -> Self {
+        self.width_mode = width_mode;
+        self
+    }
+
+    /// The maximum time spent solving
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Lowers the time limit to the given duration if it is larger or absent,
+    /// e.g. so that a solve does not overrun the time left for a whole batch
+    pub(crate) fn cap_timeout(mut self, remaining: Duration) -> Self {
+        self.timeout = Some(self.timeout.map_or(remaining, |t| t.min(remaining)));
+        self
+    }
+
+    /// Removes the time limit
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Stops the search once this many nodes were expanded
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// The number of threads used by the solver, 0 uses all logical cores
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    pub fn solver(mut self, solver: SolverType) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    pub fn fringe(mut self, fringe: FringeType) -> Self {
+        self.fringe = fringe;
+        self
+    }
+
+    pub fn objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Minimizes the given cost model, which takes precedence over the objective
+    pub fn cost_model(mut self, cost_model: Arc<dyn CostModel>) -> Self {
+        self.cost_model = Some(cost_model);
+        self
+    }
+
+    /// Makes each day on location after the first `days` days of the shooting
+    /// cost `overtime_rate` times the daily cost of the actors
+    pub fn deadline(mut self, days: usize, overtime_rate: usize) -> Self {
+        self.deadline = Some((days, overtime_rate));
+        self
+    }
+
+    /// Stops the search once the process uses more than this many MB of memory
+    pub fn max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = Some(max_memory);
+        self
+    }
+
+    /// Stops the search once the best solution has not improved for this long.
+    /// The ddo solvers do not report their solutions before the end of the
+    /// search, so this is only meaningful with the depth-first solver.
+    pub fn stall_timeout(mut self, stall_timeout: Duration) -> Self {
+        self.stall_timeout = Some(stall_timeout);
+        self
+    }
+
+    /// Only compiles one restricted decision diagram, without proving optimality
+    pub fn heuristic_only(mut self, heuristic_only: bool) -> Self {
+        self.heuristic_only = heuristic_only;
+        self
+    }
+
+    /// Stops at the first complete schedule found by a greedy depth-first
+    /// descent, without proving optimality
+    pub fn first_feasible(mut self, first_feasible: bool) -> Self {
+        self.first_feasible = first_feasible;
+        self
+    }
+
+    /// Yields the same value, bound and solution whatever the number of threads.
+    /// This relies on running the search on a single thread: the barrier solver
+    /// hands the subproblems to its threads in an order that depends on their
+    /// timing. The ties of the ranking are also broken on the states, so that
+    /// the nodes kept in a layer do not depend on the order of their creation.
+    /// Only the time limits can still make two runs differ.
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// The fractional part below which the bounds of the relaxation are rounded
+    /// down instead of up, between 0 (always round up) and 1
+    pub fn rounding_epsilon(mut self, rounding_epsilon: f64) -> Self {
+        self.rounding_epsilon = rounding_epsilon;
+        self
+    }
+
+    pub(crate) fn monitor(mut self, monitor: Arc<SearchMonitor>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Replaces the monitor by a new one sharing its output, so that a clone
+    /// of the builder does not share the progress of the searches run by the
+    /// original one
+    pub(crate) fn restart_monitor(mut self) -> Self {
+        self.monitor = self.monitor.map(|monitor| Arc::new(monitor.restart()));
+        self
+    }
+
+    /// Solves the given instance with the configured settings, or returns an
+    /// error if the instance is not valid or if some entries of its actor
+    /// matrix are unknown, which must be resolved with `resolve_unknown` first
+    pub fn run(&self, instance: &TalentSchedInstance) -> Result<SolveResult, String> {
+        if !(0.0..1.0).contains(&self.rounding_epsilon) {
+            return Err(format!("the rounding epsilon must be between 0 and 1, got {}", self.rounding_epsilon));
+        }
+        instance.validate()?;
+        let unknown = instance.unknown_entries();
+        if unknown > 0 {
+            return Err(format!("the actor matrix has {unknown} unknown entries, they must be resolved before solving"));
+        }
+        Ok(self.run_problem(&self.problem(instance.clone())))
+    }
+
+    pub(crate) fn problem(&self, instance: TalentSchedInstance) -> TalentSched {
+        let problem = match self.cost_model.clone() {
+            Some(cost_model) => TalentSched::new(instance).with_cost_model(cost_model),
+            None => TalentSched::new(instance).with_objective(self.objective),
+        };
+        match self.deadline {
+            Some((days, overtime_rate)) => problem.with_deadline(days, overtime_rate),
+            None => problem,
+        }
+    }
+
+    pub(crate) fn run_problem(&self, problem: &TalentSched) -> SolveResult {
+        let relaxation = self.relaxation(problem);
+
+        let width: Box<dyn WidthHeuristic<TalentSchedState> + Send + Sync> = match self.width_mode {
+            WidthMode::Fixed => Box::new(FixedWidth(self.width)),
+            WidthMode::Adaptive => Box::new(AdaptiveWidth::new(self.width)),
+            WidthMode::Linear => Box::new(LinearWidth(self.width)),
+            WidthMode::NbVars => Box::new(NbVarsWidth { multiplier: self.width, nb_variables: problem.nb_variables() }),
+        };
+        let monitor = self.monitor.clone().unwrap_or_default();
+        let cutoff = self.cutoff(problem, &monitor);
+        let ranking = TalentSchedRanking { deterministic: self.deterministic };
+        let mut fringe = MonitoredFringe::new(self.fringe(&ranking), monitor.clone(), problem.stats.clone());
+
+        let root_bound = problem.initial_value() + relaxation.fast_upper_bound(&problem.initial_state());
+
+        let start = Instant::now();
+        monitor.on_start();
+        let (Completion{best_value, is_exact}, order, best_bound) = match self.solver_type() {
+            _ if self.first_feasible => {
+                let (completion, order) = DepthFirstSearch::new(problem, &relaxation, &cutoff).with_monitor(monitor.clone()).first_feasible();
+                (completion, order, root_bound)
+            },
+            _ if self.heuristic_only => {
+                let (completion, order) = restricted_solution(problem, &relaxation, &ranking, &cutoff, self.width);
+                (completion, order, root_bound)
+            },
+            SolverType::Barrier => {
+                let mut solver = ParBarrierSolverFc::custom(problem, &relaxation, &ranking, width.as_ref(), &cutoff, &mut fringe, self.nb_threads());
+                let completion = solver.maximize();
+                let order = solver.best_solution().map(|mut solution| {
+                    solution.sort_unstable_by_key(|d| d.variable.id());
+                    solution.iter().map(|d| d.value as usize).collect()
+                });
+                (completion, order, solver.best_upper_bound())
+            },
+            SolverType::Dfs => {
+                let (completion, order) = DepthFirstSearch::new(problem, &relaxation, &cutoff).with_monitor(monitor.clone()).maximize();
+                let best_bound = if completion.is_exact { completion.best_value.unwrap_or(root_bound) } else { root_bound };
+                (completion, order, best_bound)
+            },
+        };
+        monitor.on_completion(is_exact, best_value);
+
+        SolveResult {
+            is_exact,
+            best_value: best_value.map(value_to_cost),
+            best_bound: value_to_cost(best_bound),
+            solution: order.unwrap_or_default(),
+            duration: start.elapsed().as_secs_f64(),
+            explored: problem.stats.explored(),
+            stopped_by: cutoff.triggered().map(|s| s.to_string()),
+        }
+    }
+
+    pub(crate) fn relaxation(&self, problem: &TalentSched) -> TalentSchedRelax {
+        TalentSchedRelax::new(problem.clone()).with_rounding_epsilon(self.rounding_epsilon)
+    }
+
+    /// The barrier solver always runs worker threads, which WebAssembly does not
+    /// support, so the depth-first solver is used instead
+    fn solver_type(&self) -> SolverType {
+        if cfg!(target_arch = "wasm32") {
+            SolverType::Dfs
+        } else {
+            self.solver
+        }
+    }
+
+    pub(crate) fn nb_threads(&self) -> usize {
+        if self.deterministic {
+            1
+        } else if self.threads == 0 {
+            thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+        } else {
+            self.threads
+        }
+    }
+
+    fn fringe<'a>(&self, ranking: &'a TalentSchedRanking) -> Box<dyn Fringe<State = TalentSchedState> + Send + Sync + 'a> {
+        match self.fringe {
+            FringeType::NoDup => Box::new(NoDupFringe::new(MaxUB::new(ranking))),
+            FringeType::Simple => Box::new(SimpleFringe::new(MaxUB::new(ranking))),
+        }
+    }
+
+    fn cutoff(&self, problem: &TalentSched, monitor: &Arc<SearchMonitor>) -> AnyCutoff {
+        let mut cutoffs: Vec<(&'static str, Box<dyn Cutoff + Send + Sync>)> = vec![];
+        if let Some(timeout) = self.timeout {
+            cutoffs.push(("timeout", time_budget(timeout)));
+        }
+        if let Some(max_nodes) = self.max_nodes {
+            cutoffs.push(("max-nodes", Box::new(NodeLimit::new(problem.stats.clone(), max_nodes))));
+        }
+        if let Some(max_memory) = self.max_memory {
+            cutoffs.push(("max-memory", Box::new(MemoryLimit::new(max_memory))));
+        }
+        if let Some(stall_timeout) = self.stall_timeout {
+            cutoffs.push(("stall-timeout", Box::new(StallTimeout::new(monitor.clone(), stall_timeout))));
+        }
+        AnyCutoff::new(cutoffs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instance::UNKNOWN;
+
+    use super::*;
+
+    #[test]
+    fn run_rejects_invalid_instances() {
+        let instance = TalentSchedInstance {
+            nb_scenes: 2,
+            nb_actors: 1,
+            cost: vec![1],
+            duration: vec![1],
+            actors: vec![vec![1, 0]],
+            contiguous: vec![],
+        };
+        assert!(SolveBuilder::new().run(&instance).is_err());
+    }
+
+    #[test]
+    fn run_rejects_unknown_entries() {
+        let mut instance = TalentSchedInstance {
+            nb_scenes: 2,
+            nb_actors: 1,
+            cost: vec![1],
+            duration: vec![1, 1],
+            actors: vec![vec![1, UNKNOWN]],
+            contiguous: vec![],
+        };
+        assert!(SolveBuilder::new().run(&instance).is_err());
+
+        instance.resolve_unknown(false);
+        assert!(SolveBuilder::new().run(&instance).is_ok());
+    }
+
+    #[test]
+    fn run_rejects_invalid_rounding_epsilons() {
+        let instance = TalentSchedInstance {
+            nb_scenes: 2,
+            nb_actors: 1,
+            cost: vec![1],
+            duration: vec![1, 1],
+            actors: vec![vec![1, 0]],
+            contiguous: vec![],
+        };
+        for epsilon in [-0.1, 1.0, f64::NAN] {
+            assert!(SolveBuilder::new().rounding_epsilon(epsilon).run(&instance).is_err());
+        }
+        assert!(SolveBuilder::new().rounding_epsilon(0.0).run(&instance).is_ok());
+    }
+}
@@ -0,0 +1,70 @@
+//! This module checks the validity of the fast upper bound of the relaxation
+//! on small instances. The optimal cost to complete the schedule is computed
+//! by brute force for every set of remaining scenes, and compared against the
+//! bound computed for the corresponding state.
+//!
+//! The scenes that can be shot next only depend on the remaining scenes, even
+//! with contiguity constraints: an actor who already played and still has to
+//! play must play in the next scene. So the brute force follows the domain of
+//! the model, and the sets of remaining scenes that cannot be completed
+//! without breaking a contiguity constraint have no optimal value.
+
+use ddo::{Decision, Problem, Relaxation, Variable};
+use smallbitset::Set64;
+
+use crate::resolution::model::{TalentSched, TalentSchedRelax, TalentSchedState};
+
+/// The largest number of scenes that can be checked, the brute force keeps
+/// one value for each of the 2^n sets of remaining scenes
+pub const MAX_CHECK_SCENES: usize = 25;
+
+/// A state for which the fast upper bound is tighter than the optimal value
+#[derive(Debug, Clone)]
+pub struct BoundViolation {
+    pub remaining: Vec<usize>,
+    pub bound: isize,
+    pub optimal: isize,
+}
+
+/// Returns all the states where the bound is not valid, along with the
+/// optimal value of the whole problem, None when no schedule satisfies the
+/// contiguity constraints
+pub fn check_bound(problem: &TalentSched, relaxation: &TalentSchedRelax) -> (Option<isize>, Vec<BoundViolation>) {
+    let n = problem.instance.nb_scenes;
+    assert!(n <= MAX_CHECK_SCENES, "cannot check an instance with {n} scenes");
+
+    let mut optimal = vec![None; 1 << n];
+    optimal[0] = Some(0_isize);
+    let mut violations = vec![];
+
+    for mask in 1_usize..(1 << n) {
+        let state = state_of(mask, n);
+        let depth = n - mask.count_ones() as usize;
+
+        let mut decisions = vec![];
+        problem.for_each_in_domain(Variable(depth), &state, &mut |d: Decision| decisions.push(d));
+
+        let best = decisions.into_iter()
+            .filter_map(|d| optimal[mask & !(1 << d.value)].map(|rest| problem.transition_cost(&state, d) + rest))
+            .max();
+        optimal[mask] = best;
+
+        if let Some(best) = best {
+            let bound = relaxation.fast_upper_bound(&state);
+            if bound < best {
+                violations.push(BoundViolation { remaining: state.scenes.iter().collect(), bound, optimal: best });
+            }
+        }
+    }
+
+    (optimal[(1 << n) - 1].map(|best| problem.initial_value() + best), violations)
+}
+
+fn state_of(mask: usize, n: usize) -> TalentSchedState {
+    let mut scenes = Set64::default();
+    for i in (0..n).filter(|i| mask & (1 << i) != 0) {
+        scenes.add_inplace(i);
+    }
+
+    TalentSchedState { scenes, maybe_scenes: Default::default() }
+}
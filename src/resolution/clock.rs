@@ -0,0 +1,8 @@
+//! This module provides the clock used to time the search. It also works when
+//! compiled to WebAssembly, where `std::time::Instant` is not available.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use instant::Instant;
@@ -0,0 +1,89 @@
+//! This module defines the cost models that can be minimized. Each actor on
+//! location pays a daily cost, and a cost model can add an extra cost that
+//! depends on when a scene is shot (e.g. overtime after a deadline).
+//!
+//! The fast upper bound of the relaxation only accounts for the daily costs,
+//! so it stays valid for any model whose extra costs are nonnegative. Merged
+//! states use the smallest elapsed time of the states they stand for, so the
+//! extra costs must also never decrease when the elapsed time grows.
+
+use std::{fmt::Debug, sync::Arc};
+
+use smallbitset::Set64;
+
+use crate::instance::TalentSchedInstance;
+
+pub trait CostModel: Debug + Send + Sync {
+    /// The cost of one day on location for the given actor
+    fn daily_cost(&self, instance: &TalentSchedInstance, actor: usize) -> usize;
+
+    /// Whether the extra costs depend on the elapsed time, which is not
+    /// computed otherwise
+    fn depends_on_time(&self) -> bool {
+        false
+    }
+
+    /// The extra cost of shooting a scene of the given duration after
+    /// `elapsed` days, with the given actors on location
+    fn extra_cost(&self, _daily_cost: &[usize], _present: Set64, _elapsed: usize, _duration: usize) -> usize {
+        0
+    }
+}
+
+/// Each day on location costs the daily cost of the actor
+#[derive(Debug, Clone, Copy)]
+pub struct WeightedCost;
+
+impl CostModel for WeightedCost {
+    fn daily_cost(&self, instance: &TalentSchedInstance, actor: usize) -> usize {
+        instance.cost[actor]
+    }
+}
+
+/// Each day on location costs one, whatever the actor
+#[derive(Debug, Clone, Copy)]
+pub struct LocationDays;
+
+impl CostModel for LocationDays {
+    fn daily_cost(&self, _instance: &TalentSchedInstance, _actor: usize) -> usize {
+        1
+    }
+}
+
+/// A wrap deadline on top of another cost model: each day on location after
+/// the first `days` days of the shooting costs `overtime_rate` times the daily
+/// cost of the actor instead of once. The cost of a schedule is thus the sum,
+/// over the actors and the days they are on location, of their daily cost
+/// multiplied by 1 on the days up to the deadline and by `overtime_rate` on
+/// the days after it, plus the extra costs of the other model.
+#[derive(Debug, Clone)]
+pub struct Deadline {
+    pub inner: Arc<dyn CostModel>,
+    pub days: usize,
+    /// The multiplier of the daily costs after the deadline, at least 1
+    pub overtime_rate: usize,
+}
+
+impl Deadline {
+    /// Returns the number of days of a scene of the given duration that are
+    /// shot after the deadline when the scene starts after `elapsed` days
+    fn overtime(&self, elapsed: usize, duration: usize) -> usize {
+        (elapsed + duration).saturating_sub(self.days.max(elapsed))
+    }
+}
+
+impl CostModel for Deadline {
+    fn daily_cost(&self, instance: &TalentSchedInstance, actor: usize) -> usize {
+        self.inner.daily_cost(instance, actor)
+    }
+
+    fn depends_on_time(&self) -> bool {
+        true
+    }
+
+    fn extra_cost(&self, daily_cost: &[usize], present: Set64, elapsed: usize, duration: usize) -> usize {
+        let overtime = self.overtime(elapsed, duration);
+        let daily = present.iter().map(|a| daily_cost[a]).sum::<usize>();
+        daily * (self.overtime_rate - 1) * overtime + self.inner.extra_cost(daily_cost, present, elapsed, duration)
+    }
+}
@@ -0,0 +1,156 @@
+//! This module defines the custom cutoffs used to stop the search early.
+
+use std::{sync::{Arc, OnceLock, atomic::{AtomicBool, AtomicUsize, Ordering}}, time::Duration};
+
+use ddo::Cutoff;
+
+use crate::resolution::stats::Statistics;
+
+/// Creates a cutoff stopping the search after the given time
+#[cfg(not(target_arch = "wasm32"))]
+pub fn time_budget(timeout: Duration) -> Box<dyn Cutoff + Send + Sync> {
+    Box::new(ddo::TimeBudget::new(timeout))
+}
+
+/// Creates a cutoff stopping the search after the given time. The ddo time
+/// budget relies on a background thread, which WebAssembly does not support,
+/// so the clock is polled instead.
+#[cfg(target_arch = "wasm32")]
+pub fn time_budget(timeout: Duration) -> Box<dyn Cutoff + Send + Sync> {
+    Box::new(Deadline { end: crate::resolution::clock::Instant::now() + timeout })
+}
+
+#[cfg(target_arch = "wasm32")]
+struct Deadline {
+    end: crate::resolution::clock::Instant,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Cutoff for Deadline {
+    fn must_stop(&self) -> bool {
+        crate::resolution::clock::Instant::now() >= self.end
+    }
+}
+
+/// This cutoff stops the search as soon as any of its inner cutoffs asks to
+/// stop, i.e. the first limit that is reached ends the search. Without any
+/// inner cutoff, the search is never interrupted. Each inner cutoff is named
+/// after its limit so that the one that ended the search can be reported.
+pub struct AnyCutoff {
+    cutoffs: Vec<(&'static str, Box<dyn Cutoff + Send + Sync>)>,
+    triggered: OnceLock<&'static str>,
+}
+
+impl AnyCutoff {
+    pub fn new(cutoffs: Vec<(&'static str, Box<dyn Cutoff + Send + Sync>)>) -> Self {
+        Self { cutoffs, triggered: OnceLock::new() }
+    }
+
+    /// Returns the name of the first limit that was reached, if any
+    pub fn triggered(&self) -> Option<&'static str> {
+        self.triggered.get().copied()
+    }
+}
+
+impl Cutoff for AnyCutoff {
+    fn must_stop(&self) -> bool {
+        if self.triggered.get().is_some() {
+            return true;
+        }
+        match self.cutoffs.iter().find(|(_, c)| c.must_stop()) {
+            Some((name, _)) => {
+                self.triggered.get_or_init(|| name);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/// This cutoff stops the search once a given number of nodes were expanded
+pub struct NodeLimit {
+    stats: Arc<Statistics>,
+    max_nodes: usize,
+}
+
+impl NodeLimit {
+    pub fn new(stats: Arc<Statistics>, max_nodes: usize) -> Self {
+        Self { stats, max_nodes }
+    }
+}
+
+impl Cutoff for NodeLimit {
+    fn must_stop(&self) -> bool {
+        self.stats.explored() >= self.max_nodes
+    }
+}
+
+/// The number of calls to `MemoryLimit::must_stop` between two reads of the
+/// memory of the process
+const MEMORY_CHECK_INTERVAL: usize = 1024;
+
+/// This cutoff stops the search when the resident memory of the process
+/// exceeds a given amount of megabytes. The cutoff is polled for every node,
+/// so the memory is only read once every `MEMORY_CHECK_INTERVAL` calls, and
+/// the limit is remembered once it was exceeded.
+pub struct MemoryLimit {
+    max_memory: usize,
+    calls: AtomicUsize,
+    exceeded: AtomicBool,
+}
+
+impl MemoryLimit {
+    pub fn new(max_memory: usize) -> Self {
+        Self { max_memory, calls: AtomicUsize::new(0), exceeded: AtomicBool::new(false) }
+    }
+}
+
+impl Cutoff for MemoryLimit {
+    fn must_stop(&self) -> bool {
+        if self.exceeded.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.calls.fetch_add(1, Ordering::Relaxed) % MEMORY_CHECK_INTERVAL != 0 {
+            return false;
+        }
+        let exceeded = current_memory().map(|m| m > self.max_memory).unwrap_or(false);
+        if exceeded {
+            self.exceeded.store(true, Ordering::Relaxed);
+        }
+        exceeded
+    }
+}
+
+/// Returns the current resident memory of the process in megabytes, if the
+/// platform allows us to read it
+#[cfg(target_os = "linux")]
+pub fn current_memory() -> Option<usize> {
+    read_status_field("VmRSS:")
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn current_memory() -> Option<usize> {
+    None
+}
+
+/// Returns the peak resident memory of the process in megabytes, if the
+/// platform allows us to read it
+#[cfg(target_os = "linux")]
+pub fn peak_memory() -> Option<usize> {
+    read_status_field("VmHWM:")
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn peak_memory() -> Option<usize> {
+    None
+}
+
+/// Reads a memory field (expressed in kB) from /proc/self/status and
+/// converts it to megabytes
+#[cfg(target_os = "linux")]
+fn read_status_field(field: &str) -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with(field))?;
+    let kb = line[field.len()..].trim().trim_end_matches("kB").trim().parse::<usize>().ok()?;
+    Some(kb / 1024)
+}
@@ -0,0 +1,156 @@
+//! This module implements a depth-first branch-and-bound over the TalentSched
+//! model. It relies on the fast upper bound of the relaxation to prune the
+//! nodes that cannot lead to a solution of the requested quality.
+
+use std::sync::Arc;
+
+use ddo::{Completion, Cutoff, Decision, Problem, Relaxation};
+
+use crate::resolution::model::{TalentSched, TalentSchedRelax, TalentSchedState};
+use crate::resolution::monitor::SearchMonitor;
+
+/// The best solution found so far by the branch-and-bound
+struct Incumbent {
+    value: Option<isize>,
+    solution: Vec<usize>,
+    aborted: bool,
+    /// Whether the search stops at the first solution found
+    first_only: bool,
+}
+
+pub struct DepthFirstSearch<'a> {
+    problem: &'a TalentSched,
+    relaxation: &'a TalentSchedRelax,
+    cutoff: &'a dyn Cutoff,
+    monitor: Option<Arc<SearchMonitor>>,
+}
+
+impl<'a> DepthFirstSearch<'a> {
+    pub fn new(problem: &'a TalentSched, relaxation: &'a TalentSchedRelax, cutoff: &'a dyn Cutoff) -> Self {
+        Self { problem, relaxation, cutoff, monitor: None }
+    }
+
+    /// Reports each new best solution to the given monitor
+    pub fn with_monitor(mut self, monitor: Arc<SearchMonitor>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Searches for the schedule of maximum value, returns the completion of the
+    /// search along with the best scene order found
+    pub fn maximize(&self) -> (Completion, Option<Vec<usize>>) {
+        self.search(false)
+    }
+
+    /// Stops at the first complete schedule met by the search, i.e. the one
+    /// obtained by greedily picking the cheapest decision at each step, unless
+    /// the contiguity constraints lead to a dead end. The completion is only
+    /// exact when there is no schedule at all.
+    pub fn first_feasible(&self) -> (Completion, Option<Vec<usize>>) {
+        let (completion, solution) = self.search(true);
+        let is_exact = completion.is_exact && completion.best_value.is_none();
+        (Completion { is_exact, best_value: completion.best_value }, solution)
+    }
+
+    fn search(&self, first_only: bool) -> (Completion, Option<Vec<usize>>) {
+        let mut incumbent = Incumbent { value: None, solution: vec![], aborted: false, first_only };
+        let mut path = vec![];
+
+        self.maximize_from(&self.problem.initial_state(), self.problem.initial_value(), &mut path, &mut incumbent);
+
+        let completion = Completion { is_exact: !incumbent.aborted, best_value: incumbent.value };
+        let solution = incumbent.value.map(|_| incumbent.solution);
+
+        (completion, solution)
+    }
+
+    fn maximize_from(&self, state: &TalentSchedState, value: isize, path: &mut Vec<usize>, incumbent: &mut Incumbent) {
+        if incumbent.aborted || (incumbent.first_only && incumbent.value.is_some()) {
+            return;
+        }
+        if self.cutoff.must_stop() {
+            incumbent.aborted = true;
+            return;
+        }
+
+        let variable = match self.problem.variable_at(path.len()) {
+            Some(variable) => variable,
+            None => {
+                if incumbent.value.map(|v| value > v).unwrap_or(true) {
+                    incumbent.value = Some(value);
+                    incumbent.solution = path.clone();
+                    if let Some(monitor) = self.monitor.as_ref() {
+                        monitor.on_incumbent(value);
+                    }
+                }
+                return;
+            },
+        };
+
+        if let Some(best) = incumbent.value {
+            if value + self.relaxation.fast_upper_bound(state) <= best {
+                return;
+            }
+        }
+
+        // explore the cheapest decisions first to find good solutions early
+        let mut children = self.decisions(variable, state).into_iter()
+            .map(|d| (d, value + self.problem.transition_cost(state, d)))
+            .collect::<Vec<(Decision, isize)>>();
+        children.sort_unstable_by_key(|(_, v)| -v);
+
+        for (decision, child_value) in children {
+            let next = self.problem.transition(state, decision);
+
+            path.push(decision.value as usize);
+            self.maximize_from(&next, child_value, path, incumbent);
+            path.pop();
+        }
+    }
+
+    /// Collects at most `max_solutions` distinct scene orders whose value is
+    /// equal to `target`
+    pub fn enumerate(&self, target: isize, max_solutions: usize) -> Vec<Vec<usize>> {
+        let mut solutions = vec![];
+        let mut path = vec![];
+
+        self.enumerate_from(&self.problem.initial_state(), self.problem.initial_value(), &mut path, target, max_solutions, &mut solutions);
+
+        solutions
+    }
+
+    fn enumerate_from(&self, state: &TalentSchedState, value: isize, path: &mut Vec<usize>, target: isize, max_solutions: usize, solutions: &mut Vec<Vec<usize>>) {
+        if solutions.len() >= max_solutions || self.cutoff.must_stop() {
+            return;
+        }
+
+        let variable = match self.problem.variable_at(path.len()) {
+            Some(variable) => variable,
+            None => {
+                if value == target {
+                    solutions.push(path.clone());
+                }
+                return;
+            },
+        };
+
+        if value + self.relaxation.fast_upper_bound(state) < target {
+            return;
+        }
+
+        for decision in self.decisions(variable, state) {
+            let next = self.problem.transition(state, decision);
+            let cost = self.problem.transition_cost(state, decision);
+
+            path.push(decision.value as usize);
+            self.enumerate_from(&next, value + cost, path, target, max_solutions, solutions);
+            path.pop();
+        }
+    }
+
+    fn decisions(&self, variable: ddo::Variable, state: &TalentSchedState) -> Vec<Decision> {
+        let mut decisions = vec![];
+        self.problem.for_each_in_domain(variable, state, &mut |d| decisions.push(d));
+        decisions
+    }
+}
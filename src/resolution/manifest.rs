@@ -0,0 +1,92 @@
+//! This module solves a list of instances given in a manifest file, e.g.
+//! `[{"instance": "a.json", "width": 1000}, {"instance": "b.json", "timeout": 10}]`.
+//! The settings of each entry override those given on the command line. The
+//! manifests written by the generator can be used as is.
+
+use std::{fs::File, io::BufReader, path::Path, time::Duration};
+
+use serde::Deserialize;
+
+use crate::resolution::builder::{SolveBuilder, SolveStatus};
+use crate::resolution::clock::Instant;
+use crate::resolution::table::{ResultRow, TableFormat};
+use crate::instance::TalentSchedInstance;
+
+/// An instance to solve, along with the settings specific to it
+#[derive(Debug, Deserialize)]
+pub struct ManifestEntry {
+    /// The path to the instance file, relative to the manifest
+    #[serde(alias = "file")]
+    pub instance: String,
+    pub width: Option<usize>,
+    /// The timeout in seconds, 0 means no time limit
+    pub timeout: Option<u64>,
+}
+
+/// Solves each instance of the manifest at the given path and prints one
+/// row per instance. Once the total timeout is reached, the remaining
+/// instances are skipped and count as failures. Returns the worst status
+/// among them.
+pub fn solve_manifest(path: &str, builder: &SolveBuilder, format: TableFormat, total_timeout: Option<Duration>) -> SolveStatus {
+    let entries: Vec<ManifestEntry> = match File::open(path).map_err(|e| e.to_string())
+        .and_then(|f| serde_json::from_reader(BufReader::new(f)).map_err(|e| e.to_string())) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("error: cannot read the manifest {path}: {e}");
+            return SolveStatus::Failed;
+        }
+    };
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+
+    let start = Instant::now();
+    let mut results = vec![];
+    for entry in entries.iter() {
+        let remaining = total_timeout.map(|total| total.saturating_sub(start.elapsed()));
+        if remaining.map(|r| r.is_zero()).unwrap_or(false) {
+            eprintln!("skipped {}: the total timeout was reached", entry.instance);
+            results.push(None);
+            continue;
+        }
+
+        let instance_path = dir.join(&entry.instance);
+        let instance = match TalentSchedInstance::try_read(&instance_path.to_string_lossy()) {
+            Ok(instance) => instance,
+            Err(e) => {
+                eprintln!("error: {e}");
+                results.push(None);
+                continue;
+            }
+        };
+
+        let mut builder = builder.clone().restart_monitor();
+        if let Some(width) = entry.width {
+            builder = builder.width(width);
+        }
+        match entry.timeout {
+            Some(0) => builder = builder.without_timeout(),
+            Some(timeout) => builder = builder.timeout(Duration::from_secs(timeout)),
+            None => (),
+        }
+        if let Some(remaining) = remaining {
+            builder = builder.cap_timeout(remaining);
+        }
+
+        match builder.run(&instance) {
+            Ok(result) => results.push(Some(result)),
+            Err(e) => {
+                eprintln!("error: {}: {e}", entry.instance);
+                results.push(None);
+            }
+        }
+    }
+
+    let rows = entries.iter().zip(results.iter())
+        .filter_map(|(e, r)| r.clone().map(|result| ResultRow { instance: e.instance.clone(), result }))
+        .collect::<Vec<ResultRow>>();
+    print!("{}", format.formatter().format(&rows));
+
+    results.iter()
+        .map(|r| r.as_ref().map(|r| r.status()).unwrap_or(SolveStatus::Failed))
+        .max_by_key(|s| s.exit_code())
+        .unwrap_or(SolveStatus::Optimal)
+}
@@ -1,4 +1,29 @@
 mod solve;
+mod builder;
 mod model;
+mod cost;
+mod cutoff;
+mod monitor;
+mod stats;
+mod dfs;
+mod restricted;
+mod check;
+mod clock;
+mod svg;
+mod manifest;
+mod width;
+mod options;
+mod report;
+mod batch;
+mod table;
 
-pub use solve::*;
\ No newline at end of file
+pub use solve::*;
+pub use builder::*;
+pub use model::Objective;
+pub use cost::{CostModel, Deadline, LocationDays, WeightedCost};
+pub use manifest::ManifestEntry;
+pub use width::WidthMode;
+pub use options::{SolveOptions, SolveOutcome, solve_instance};
+pub use report::SolveReport;
+pub use batch::SolveBatch;
+pub use table::TableFormat;
@@ -1,10 +1,32 @@
-use std::vec;
+use std::{sync::Arc, vec};
 
+use clap::ValueEnum;
 use ddo::*;
 use ordered_float::OrderedFloat;
+use serde::{Serialize, Deserialize};
 use smallbitset::Set64;
 
 use crate::instance::TalentSchedInstance;
+use crate::resolution::cost::{CostModel, Deadline, LocationDays, WeightedCost};
+use crate::resolution::stats::Statistics;
+
+/// The default fractional part below which the fast upper bound is rounded
+/// down instead of up, see `round_bound`
+pub const ROUNDING_EPSILON: f64 = 1e-2;
+
+/// ddo maximizes the value of the solutions while the talent scheduling
+/// minimizes their cost, so the model works with values that are the opposite
+/// of the costs: a cost of 10 is a value of -10, and the upper bounds on the
+/// values computed by the relaxation are lower bounds on the costs. These two
+/// functions are the only places where the conversion happens.
+pub fn cost_to_value(cost: isize) -> isize {
+    -cost
+}
+
+/// Converts a value of the model back to the cost it stands for, see `cost_to_value`
+pub fn value_to_cost(value: isize) -> isize {
+    -value
+}
 
 /// The state of the DP model
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -13,26 +35,200 @@ pub struct TalentSchedState {
     pub maybe_scenes: Set64,
 }
 
+/// The objective function that is minimized, among the predefined cost models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Objective {
+    /// Each day on location costs the daily cost of the actor
+    WeightedCost,
+    /// Each day on location costs one, whatever the actor
+    LocationDays,
+}
+
+impl Objective {
+    pub fn cost_model(&self) -> Arc<dyn CostModel> {
+        match self {
+            Objective::WeightedCost => Arc::new(WeightedCost),
+            Objective::LocationDays => Arc::new(LocationDays),
+        }
+    }
+}
+
 /// This structure describes a TalentSched instance
 #[derive(Debug, Clone)]
 pub struct TalentSched {
     pub instance: TalentSchedInstance,
     pub actors: Vec<Set64>,
+    /// The cost of one day on location for each actor, given the objective
+    pub cost: Vec<usize>,
+    /// The sum and the sum of squares of the daily costs of the actors of each scene
+    pub scene_cost: Vec<(f64, f64)>,
+    /// The actors whose scenes must be shot in a single block
+    pub contiguous: Set64,
+    /// The cost model that is minimized
+    pub cost_model: Arc<dyn CostModel>,
+    pub stats: Arc<Statistics>,
 }
 
 impl TalentSched {
-    pub fn new(instance: TalentSchedInstance) -> Self {
+    /// Creates the model of the given instance, where any nonzero entry of the
+    /// actor matrix is normalized to 1
+    pub fn new(mut instance: TalentSchedInstance) -> Self {
+        instance.normalize();
+
         let mut actors = vec![Set64::default(); instance.nb_scenes];
 
         for i in 0..instance.nb_actors {
             for j in 0..instance.nb_scenes {
-                if instance.actors[i][j] == 1 {
+                if instance.plays(i, j) {
                     actors[j].add_inplace(i);
                 }
             }
         }
 
-        TalentSched {instance, actors }
+        let mut contiguous = Set64::default();
+        for (i, _) in instance.contiguous.iter().enumerate().filter(|(_, c)| **c) {
+            contiguous.add_inplace(i);
+        }
+
+        let cost = instance.cost.clone();
+        let scene_cost = Self::compute_scene_cost(&actors, &cost);
+        let stats = Arc::new(Statistics::new(instance.nb_scenes));
+
+        TalentSched {instance, actors, cost, scene_cost, contiguous, cost_model: Arc::new(WeightedCost), stats }
+    }
+
+    pub fn with_objective(self, objective: Objective) -> Self {
+        self.with_cost_model(objective.cost_model())
+    }
+
+    /// Minimizes the given cost model instead of the weighted cost
+    pub fn with_cost_model(mut self, cost_model: Arc<dyn CostModel>) -> Self {
+        self.cost = (0..self.instance.nb_actors).map(|a| cost_model.daily_cost(&self.instance, a)).collect();
+        self.scene_cost = Self::compute_scene_cost(&self.actors, &self.cost);
+        self.cost_model = cost_model;
+        self
+    }
+
+    /// Makes the days on location after the given deadline cost `overtime_rate`
+    /// times the daily cost of the actors, the rate must be at least 1
+    pub fn with_deadline(self, days: usize, overtime_rate: usize) -> Self {
+        assert!(overtime_rate >= 1, "the overtime rate must be at least 1");
+        let inner = self.cost_model.clone();
+        self.with_cost_model(Arc::new(Deadline { inner, days, overtime_rate }))
+    }
+
+    fn compute_scene_cost(actors: &[Set64], cost: &[usize]) -> Vec<(f64, f64)> {
+        actors.iter().map(|scene| Self::cost_sums(scene, cost)).collect()
+    }
+
+    fn cost_sums(actors: &Set64, cost: &[usize]) -> (f64, f64) {
+        let mut total_cost = 0.0;
+        let mut total_cost_sq = 0.0;
+
+        for actor in actors.iter() {
+            total_cost += cost[actor] as f64;
+            total_cost_sq += (cost[actor] * cost[actor]) as f64;
+        }
+
+        (total_cost, total_cost_sq)
+    }
+
+    /// Computes the cost of shooting the scenes in the given order under the
+    /// cost model, including its extra costs if any
+    pub fn schedule_cost(&self, order: &[usize]) -> usize {
+        if !self.cost_model.depends_on_time() {
+            return self.instance.schedule_cost_with(order, &self.cost);
+        }
+
+        let mut cost = 0;
+        let mut elapsed = 0;
+        for (scene, present) in order.iter().copied().zip(self.presence(order)) {
+            let duration = self.instance.duration[scene];
+            cost += present.iter().map(|a| self.cost[a] * duration).sum::<usize>();
+            cost += self.cost_model.extra_cost(&self.cost, present, elapsed, duration);
+            elapsed += duration;
+        }
+        cost
+    }
+
+    /// Computes the part of the cost of the schedule paid for the given actor
+    /// under the cost model, i.e. their days on location and their share of
+    /// the extra costs
+    pub fn actor_cost(&self, order: &[usize], actor: usize) -> usize {
+        let mut alone = Set64::default();
+        alone.add_inplace(actor);
+
+        let mut cost = 0;
+        let mut elapsed = 0;
+        for (scene, present) in order.iter().copied().zip(self.presence(order)) {
+            let duration = self.instance.duration[scene];
+            if present.contains(actor) {
+                cost += self.cost[actor] * duration + self.cost_model.extra_cost(&self.cost, alone, elapsed, duration);
+            }
+            elapsed += duration;
+        }
+        cost
+    }
+
+    /// Returns the smallest number of days already shot among the states
+    /// merged into the given one. The scenes left to shoot in each of them
+    /// are a subset of `scenes` and `maybe_scenes`, so at least the other
+    /// scenes were shot. For an exact state, this is the exact number of days.
+    fn min_elapsed(&self, state: &TalentSchedState) -> usize {
+        let remaining = state.scenes.union(state.maybe_scenes);
+        (0..self.instance.nb_scenes)
+            .filter(|s| !remaining.contains(*s))
+            .map(|s| self.instance.duration[s])
+            .sum()
+    }
+
+    /// Returns the variable decided at the given depth, if any. Unlike
+    /// `next_variable`, this records nothing about the layers of the decision
+    /// diagrams, so it is what the searches that do not compile any use.
+    pub fn variable_at(&self, depth: usize) -> Option<Variable> {
+        if depth < self.instance.nb_scenes {
+            Some(Variable(depth))
+        } else {
+            None
+        }
+    }
+
+    /// Builds a schedule by repeatedly shooting the scene with the smallest
+    /// incremental cost given the actors already on location, among those that
+    /// keep the blocks of the contiguous actors unbroken when there are any
+    pub fn greedy_schedule(&self) -> Vec<usize> {
+        let mut order = vec![];
+        let mut state = self.initial_state();
+
+        for position in 0..self.instance.nb_scenes {
+            let required = self.get_present(&state).inter(self.contiguous);
+            let allowed = state.scenes.iter().filter(|i| required.diff(self.actors[*i]).is_empty()).collect::<Vec<usize>>();
+            let candidates = if allowed.is_empty() { state.scenes.iter().collect() } else { allowed };
+
+            let decision = candidates.into_iter()
+                .map(|scene| Decision { variable: Variable(position), value: scene as isize })
+                .max_by_key(|d| (self.transition_cost(&state, *d), -d.value))
+                .unwrap();
+            order.push(decision.value as usize);
+            state = self.transition(&state, decision);
+        }
+
+        order
+    }
+
+    /// Returns, for each position of the given order, the set of actors that
+    /// are on location while the scene is shot
+    pub fn presence(&self, order: &[usize]) -> Vec<Set64> {
+        let mut presence = vec![];
+        let mut state = self.initial_state();
+
+        for (position, scene) in order.iter().copied().enumerate() {
+            presence.push(self.get_present(&state).union(self.actors[scene]));
+            state = self.transition(&state, Decision { variable: Variable(position), value: scene as isize });
+        }
+
+        presence
     }
 
     fn get_present(&self, state: &TalentSchedState) -> Set64 {
@@ -73,13 +269,7 @@ impl Problem for TalentSched {
     }
 
     fn initial_value(&self) -> isize {
-        let mut cost = 0;
-        for (scene, actors) in self.actors.iter().enumerate() {
-            for actor in actors.iter() {
-                cost += self.instance.cost[actor] * self.instance.duration[scene];
-            }
-        }
-        - (cost as isize)
+        cost_to_value(self.instance.appearance_cost_with(&self.cost) as isize)
     }
 
     fn transition(&self, state: &Self::State, decision: ddo::Decision) -> Self::State {
@@ -98,33 +288,61 @@ impl Problem for TalentSched {
 
         let mut cost = 0;
         for actor in pay.iter() {
-            cost += self.instance.cost[actor] * self.instance.duration[scene];
+            cost += self.cost[actor] * self.instance.duration[scene];
         }
 
-        - (cost as isize)
+        // The days where the actors play are already counted once in the
+        // initial value, only the extra costs of the model are paid here.
+        // Using the smallest elapsed time of the merged states can only
+        // underestimate them, so the relaxation stays valid.
+        if self.cost_model.depends_on_time() {
+            let present = pay.union(self.actors[scene]);
+            cost += self.cost_model.extra_cost(&self.cost, present, self.min_elapsed(state), self.instance.duration[scene]);
+        }
+
+        cost_to_value(cost as isize)
     }
 
-    fn next_variable(&self, depth: usize, _: &mut dyn Iterator<Item = &Self::State>)
+    fn next_variable(&self, depth: usize, next_layer: &mut dyn Iterator<Item = &Self::State>)
         -> Option<ddo::Variable> {
-        if depth < self.instance.nb_scenes {
-            Some(Variable(depth))
-        } else {
-            None
-        }
+        self.stats.on_layer(depth, next_layer.count());
+
+        self.variable_at(depth)
     }
 
     fn for_each_in_domain(&self, variable: ddo::Variable, state: &Self::State, f: &mut dyn ddo::DecisionCallback) {
+        self.stats.on_expand(self.instance.nb_scenes - variable.id());
+
+        // The actors with a contiguity constraint who are already on location
+        // must play in the next scene, otherwise their block would have a gap.
+        // In a merged state, the present actors are a subset of those of each
+        // merged state, so this never removes a feasible decision of any of them.
+        let required = self.get_present(state).inter(self.contiguous);
+        let allowed = |i: usize| required.diff(self.actors[i]).is_empty();
+
         let mut count = 0;
 
         for i in state.scenes.iter() {
-            f.apply(Decision { variable, value: i as isize });
+            if allowed(i) {
+                f.apply(Decision { variable, value: i as isize });
+            }
             count += 1;
         }
 
+        // Each state merged into a node of layer `variable.id()` still has to
+        // schedule exactly `nb_scenes - variable.id()` scenes. This relies on the
+        // invariant kept by `merge_states`: the scenes left to each merged state
+        // include all of `scenes` and are otherwise taken from `maybe_scenes`.
+        // Under it, when `scenes` already fills the remaining positions, none of
+        // the merged states has one of the `maybe_scenes` left, so they are not
+        // offered. Otherwise, any of them may fill the missing positions.
+        //
+        // For an exact state, `scenes` always fills the remaining positions and
+        // `maybe_scenes` is empty. The tests below compare the decisions offered
+        // for exact and merged states with a brute force on small instances.
         if variable.id() + count < self.instance.nb_scenes {
-            for i in state.maybe_scenes.iter() {
+            for i in state.maybe_scenes.iter().filter(|i| allowed(*i)) {
                 f.apply(Decision { variable, value: i as isize });
-                count += 1;
             }
         }
     }
@@ -133,19 +351,25 @@ impl Problem for TalentSched {
 /// This structure implements the TalentSched relaxation
 pub struct TalentSchedRelax {
     pb: TalentSched,
+    pub rounding_epsilon: f64,
 }
 
 impl TalentSchedRelax {
     pub fn new(pb: TalentSched) -> Self {
-        Self { pb }
+        Self { pb, rounding_epsilon: ROUNDING_EPSILON }
     }
-}
 
-impl Relaxation for TalentSchedRelax {
-    type State = TalentSchedState;
+    /// Sets the epsilon used to round the fast upper bound, see `round_bound`
+    pub fn with_rounding_epsilon(mut self, epsilon: f64) -> Self {
+        self.rounding_epsilon = epsilon;
+        self
+    }
 
-    fn merge(&self, states: &mut dyn Iterator<Item = &Self::State>) -> Self::State {
-        let mut merged = states.next().unwrap().clone();
+    /// Merges the given states: the scenes remaining in all of them are kept in
+    /// `scenes`, while the scenes that might remain in any of them, but not in
+    /// all, are moved to `maybe_scenes`
+    fn merge_states(states: &[&TalentSchedState]) -> TalentSchedState {
+        let mut merged = states[0].clone();
 
         for s in states {
             merged.scenes.inter_inplace(&s.scenes);
@@ -157,6 +381,16 @@ impl Relaxation for TalentSchedRelax {
         
         merged
     }
+}
+
+impl Relaxation for TalentSchedRelax {
+    type State = TalentSchedState;
+
+    fn merge(&self, states: &mut dyn Iterator<Item = &Self::State>) -> Self::State {
+        let states = states.collect::<Vec<&Self::State>>();
+        self.pb.stats.on_merge(states.len());
+        Self::merge_states(&states)
+    }
 
     fn relax(
         &self,
@@ -173,18 +407,18 @@ impl Relaxation for TalentSchedRelax {
         let mut lb = 0.0;
 
         let present_actors = self.pb.get_present(state);
+
         let mut r = (0..self.pb.instance.nb_actors).map(|i| (OrderedFloat(0.0), i)).collect::<Vec<(OrderedFloat<f64>, usize)>>();
 
         for scene in state.scenes.iter() {
             let present_actors_from_scene = self.pb.actors[scene].inter(present_actors);
             if !present_actors_from_scene.is_empty() {
-                let mut total_cost = 0.0;
-                let mut total_cost_sq = 0.0;
-
-                for actor in present_actors_from_scene.iter() {
-                    total_cost += self.pb.instance.cost[actor] as f64;
-                    total_cost_sq += (self.pb.instance.cost[actor] * self.pb.instance.cost[actor]) as f64;
-                }
+                // most of the time, all the actors of a scene are present
+                let (total_cost, total_cost_sq) = if present_actors_from_scene == self.pb.actors[scene] {
+                    self.pb.scene_cost[scene]
+                } else {
+                    TalentSched::cost_sums(&present_actors_from_scene, &self.pb.cost)
+                };
 
                 for actor in present_actors_from_scene.iter() {
                     r[actor].0 += self.pb.instance.duration[scene] as f64 / total_cost;
@@ -198,28 +432,255 @@ impl Relaxation for TalentSchedRelax {
         let mut sum_e = 0.0;
         for (r_a, a) in r {
             if present_actors.contains(a) {
-                sum_e += r_a.0 * self.pb.instance.cost[a] as f64;
-                lb += self.pb.instance.cost[a] as f64 * sum_e;
+                sum_e += r_a.0 * self.pb.cost[a] as f64;
+                lb += self.pb.cost[a] as f64 * sum_e;
             }
         }
-        
-        - (lb.ceil() as isize)
+
+        cost_to_value(round_bound(lb, self.rounding_epsilon) as isize)
     }
 }
 
+/// The costs are integers, so the fractional lower bound on the cost computed
+/// by the fast upper bound is rounded up. When its fractional part is below
+/// the epsilon, it is rounded down instead, since it is most likely a floating
+/// point error on an integer bound.
+fn round_bound(lb: f64, epsilon: f64) -> f64 {
+    debug_assert!((0.0..1.0).contains(&epsilon), "rounding epsilon {epsilon} outside of [0, 1)");
+    if lb - lb.floor() < epsilon { lb.floor() } else { lb.ceil() }
+}
+
 
 /// The last bit of information which we need to provide when implementing a ddo-based
 /// solver is a `StateRanking`. This is an heuristic which is used to select the most
 /// and least promising nodes as a means to only delete/merge the *least* promising nodes
 /// when compiling restricted and relaxed DDs.
-pub struct TalentSchedRanking;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TalentSchedRanking {
+    /// Whether the ties are broken on the scenes themselves, so that the order
+    /// of the nodes does not depend on the order in which they were created
+    pub deterministic: bool,
+}
+
 impl StateRanking for TalentSchedRanking {
     type State = TalentSchedState;
 
     fn compare(&self, a: &Self::State, b: &Self::State) -> std::cmp::Ordering {
         let tot_a = a.scenes.len() + a.maybe_scenes.len();
         let tot_b = b.scenes.len() + b.maybe_scenes.len();
-        
-        tot_a.cmp(&tot_b)
+
+        let order = tot_a.cmp(&tot_b);
+        if !self.deterministic {
+            return order;
+        }
+        order
+            .then_with(|| a.scenes.iter().cmp(b.scenes.iter()))
+            .then_with(|| a.maybe_scenes.iter().cmp(b.maybe_scenes.iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn set(elements: &[usize]) -> Set64 {
+        let mut set = Set64::default();
+        for e in elements.iter() {
+            set.add_inplace(*e);
+        }
+        set
+    }
+
+    fn mask_state(mask: usize, nb_scenes: usize) -> TalentSchedState {
+        TalentSchedState {
+            scenes: set(&(0..nb_scenes).filter(|i| mask & (1 << i) != 0).collect::<Vec<usize>>()),
+            maybe_scenes: Set64::default(),
+        }
+    }
+
+    fn small_instance(seed: u64, nb_scenes: usize, nb_actors: usize) -> TalentSchedInstance {
+        let mut rng = StdRng::seed_from_u64(seed);
+        TalentSchedInstance {
+            nb_scenes,
+            nb_actors,
+            cost: (0..nb_actors).map(|_| rng.gen_range(1..=10)).collect(),
+            duration: (0..nb_scenes).map(|_| rng.gen_range(1..=3)).collect(),
+            actors: (0..nb_actors).map(|_| (0..nb_scenes).map(|_| rng.gen_range(0..=1)).collect()).collect(),
+            contiguous: (0..nb_actors).map(|_| rng.gen_bool(0.3)).collect(),
+        }
+    }
+
+    fn domain(problem: &TalentSched, state: &TalentSchedState, depth: usize) -> BTreeSet<usize> {
+        let mut domain = BTreeSet::new();
+        problem.for_each_in_domain(Variable(depth), state, &mut |d: Decision| { domain.insert(d.value as usize); });
+        domain
+    }
+
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![vec![]];
+        }
+        let mut all = vec![];
+        for mut order in permutations(n - 1) {
+            for position in 0..n {
+                order.insert(position, n - 1);
+                all.push(order.clone());
+                order.remove(position);
+            }
+        }
+        all
+    }
+
+    /// Returns, for each set of remaining scenes, the scenes that are shot next
+    /// in at least one complete schedule satisfying the contiguity constraints
+    fn brute_force_domains(instance: &TalentSchedInstance) -> HashMap<usize, BTreeSet<usize>> {
+        let n = instance.nb_scenes;
+        let mut domains: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+        for order in permutations(n).into_iter().filter(|o| instance.violations(o).is_empty()) {
+            let mut remaining = (1 << n) - 1;
+            for scene in order {
+                domains.entry(remaining).or_default().insert(scene);
+                remaining &= !(1 << scene);
+            }
+        }
+        domains
+    }
+
+    #[test]
+    fn exact_domains_match_brute_force() {
+        let n = 6;
+        for seed in 0..20 {
+            let instance = small_instance(seed, n, 4);
+            let with_contiguity = instance.contiguous.contains(&true);
+            let feasible = brute_force_domains(&instance);
+            let problem = TalentSched::new(instance);
+
+            for mask in 1_usize..(1 << n) {
+                let state = mask_state(mask, n);
+                let depth = n - mask.count_ones() as usize;
+                let offered = domain(&problem, &state, depth);
+                let remaining = state.scenes.iter().collect::<BTreeSet<usize>>();
+
+                assert!(offered.is_subset(&remaining), "seed {seed}: scenes offered outside of {remaining:?}");
+                if let Some(feasible) = feasible.get(&mask) {
+                    assert!(feasible.is_subset(&offered), "seed {seed}: {feasible:?} is not within the domain {offered:?}");
+                }
+                if !with_contiguity {
+                    assert_eq!(offered, remaining, "seed {seed}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn merged_domains_cover_the_merged_states() {
+        let n = 5;
+        for seed in 0..10 {
+            let problem = TalentSched::new(small_instance(seed, n, 4));
+            for a in 1_usize..(1 << n) {
+                for b in (1_usize..(1 << n)).filter(|b| b.count_ones() == a.count_ones()) {
+                    let depth = n - a.count_ones() as usize;
+                    let (state_a, state_b) = (mask_state(a, n), mask_state(b, n));
+                    let merged = TalentSchedRelax::merge_states(&[&state_a, &state_b]);
+
+                    let offered = domain(&problem, &merged, depth);
+                    let expected = domain(&problem, &state_a, depth).union(&domain(&problem, &state_b, depth)).copied().collect::<BTreeSet<usize>>();
+                    let allowed = merged.scenes.union(merged.maybe_scenes).iter().collect::<BTreeSet<usize>>();
+
+                    assert!(expected.is_subset(&offered), "seed {seed}: merging {a:b} and {b:b} offers {offered:?} instead of {expected:?}");
+                    assert!(offered.is_subset(&allowed), "seed {seed}: merging {a:b} and {b:b} offers scenes outside of {allowed:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn merge_keeps_the_common_scenes_and_the_others_as_maybe() {
+        let (a, b) = (mask_state(0b0110, 4), mask_state(0b1010, 4));
+        let merged = TalentSchedRelax::merge_states(&[&a, &b]);
+        assert_eq!(merged.scenes, set(&[1]));
+        assert_eq!(merged.maybe_scenes, set(&[2, 3]));
+
+        // merging again with an exact or a merged state keeps the invariant
+        let c = mask_state(0b0011, 4);
+        let merged = TalentSchedRelax::merge_states(&[&merged, &c]);
+        assert_eq!(merged.scenes, set(&[1]));
+        assert_eq!(merged.maybe_scenes, set(&[0, 2, 3]));
+        for state in [&a, &b, &c] {
+            assert!(merged.scenes.diff(state.scenes).is_empty());
+            assert!(state.scenes.diff(merged.scenes.union(merged.maybe_scenes)).is_empty());
+        }
+    }
+
+    #[test]
+    fn merged_domain_is_a_superset_of_the_domains() {
+        let instance = TalentSchedInstance {
+            nb_scenes: 4,
+            nb_actors: 2,
+            cost: vec![1, 1],
+            duration: vec![1; 4],
+            actors: vec![vec![1, 1, 0, 0], vec![0, 1, 1, 1]],
+            contiguous: vec![],
+        };
+        let problem = TalentSched::new(instance);
+
+        let (a, b) = (mask_state(0b0110, 4), mask_state(0b1010, 4));
+        let merged = TalentSchedRelax::merge_states(&[&a, &b]);
+        assert_eq!(domain(&problem, &a, 2), BTreeSet::from([1, 2]));
+        assert_eq!(domain(&problem, &b, 2), BTreeSet::from([1, 3]));
+        assert_eq!(domain(&problem, &merged, 2), BTreeSet::from([1, 2, 3]));
+
+        // with a single position left, the scenes of both states can fill it
+        let (a, b) = (mask_state(0b0010, 4), mask_state(0b0100, 4));
+        let merged = TalentSchedRelax::merge_states(&[&a, &b]);
+        assert_eq!(domain(&problem, &merged, 3), BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn costs_and_values_are_opposite() {
+        for cost in [0, 1, 10, 1 << 40, isize::MAX, -isize::MAX] {
+            assert_eq!(value_to_cost(cost_to_value(cost)), cost);
+            assert_eq!(cost_to_value(value_to_cost(cost)), cost);
+        }
+        assert_eq!(cost_to_value(0), 0);
+        assert_eq!(cost_to_value(10), -10);
+        assert_eq!(value_to_cost(-10), 10);
+        assert_eq!(cost_to_value(isize::MAX), -isize::MAX);
+
+        // a cheaper schedule has a larger value, which ddo maximizes
+        assert!(cost_to_value(5) > cost_to_value(10));
+        assert!(value_to_cost(-5) < value_to_cost(-10));
+    }
+
+    #[test]
+    fn actor_costs_sum_to_the_schedule_cost() {
+        for seed in 0..20 {
+            let instance = small_instance(seed, 6, 4);
+            let order = (0..6).rev().collect::<Vec<usize>>();
+
+            for problem in [TalentSched::new(instance.clone()), TalentSched::new(instance.clone()).with_deadline(5, 3)] {
+                let total = (0..4).map(|actor| problem.actor_cost(&order, actor)).sum::<usize>();
+                assert_eq!(total, problem.schedule_cost(&order));
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_are_rounded_up_unless_just_above_an_integer() {
+        let epsilon = ROUNDING_EPSILON;
+        assert_eq!(round_bound(10.0, epsilon), 10.0);
+        assert_eq!(round_bound(10.0 + epsilon / 2.0, epsilon), 10.0);
+        assert_eq!(round_bound(10.0 + 2.0 * epsilon, epsilon), 11.0);
+        assert_eq!(round_bound(10.5, epsilon), 11.0);
+        assert_eq!(round_bound(11.0 - epsilon / 2.0, epsilon), 11.0);
+        assert_eq!(round_bound(-0.5, epsilon), 0.0);
+        assert_eq!(round_bound(-1.0 + epsilon / 2.0, epsilon), -1.0);
+
+        // without epsilon, the bound is always rounded up
+        assert_eq!(round_bound(10.0 + 1e-9, 0.0), 11.0);
     }
 }
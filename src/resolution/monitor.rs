@@ -0,0 +1,277 @@
+//! This module keeps track of the progress made during the search.
+//!
+//! The ddo solvers do not report anything until the search is over, so the
+//! monitor observes the subproblems popped from the fringe instead. Since the
+//! fringe is ordered by decreasing upper bound, the bound of each popped node
+//! is the best bound known for the whole problem at that time.
+//!
+//! The monitor can also write each event as a line of JSON, with the values
+//! expressed as costs (i.e. in the minimization sense), or trace each new best
+//! solution. Only the depth-first search reports its solutions as it finds
+//! them: the ddo solvers keep theirs private, so their best solution is only
+//! reported once the search is over, and `--trace` is refused with them.
+
+use std::{io::Write, sync::{Arc, Mutex}, time::Duration};
+
+use ddo::{Cutoff, Fringe, SubProblem};
+use serde::Serialize;
+
+use crate::resolution::clock::Instant;
+use crate::resolution::model::value_to_cost;
+use crate::resolution::stats::Statistics;
+
+/// An event occurring during the search
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SearchEvent {
+    Start { time: f64 },
+    Incumbent { time: f64, cost: isize },
+    Bound { time: f64, bound: isize },
+    Completion { time: f64, is_exact: bool, cost: Option<isize> },
+}
+
+/// The progress of the search observed so far
+pub struct SearchMonitor {
+    progress: Mutex<Progress>,
+    events: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
+    trace: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Progress {
+    start: Instant,
+    best_bound: isize,
+    /// When the best solution last improved, or the start of the search
+    last_improvement: Instant,
+    best_value: Option<isize>,
+}
+
+impl Progress {
+    fn new() -> Self {
+        let start = Instant::now();
+        Self { start, best_bound: isize::MAX, last_improvement: start, best_value: None }
+    }
+}
+
+impl SearchMonitor {
+    pub fn new() -> Self {
+        Self {
+            progress: Mutex::new(Progress::new()),
+            events: None,
+            trace: false,
+        }
+    }
+
+    /// Writes each event of the search as a line of JSON to the given writer
+    pub fn with_events(mut self, events: Box<dyn Write + Send>) -> Self {
+        self.events = Some(Arc::new(Mutex::new(events)));
+        self
+    }
+
+    /// Prints a line with the elapsed time and the cost of each new best solution
+    pub fn with_trace(mut self) -> Self {
+        self.trace = true;
+        self
+    }
+
+    /// Returns a monitor with no progress yet, which writes its events to the
+    /// same writer as this one and traces them in the same way
+    pub fn restart(&self) -> Self {
+        Self {
+            progress: Mutex::new(Progress::new()),
+            events: self.events.clone(),
+            trace: self.trace,
+        }
+    }
+
+    /// Records the start of the search, and forgets the progress of the
+    /// previous search when the monitor is used for several of them
+    pub fn on_start(&self) {
+        *self.progress.lock().unwrap() = Progress::new();
+        self.emit(SearchEvent::Start { time: self.time() });
+    }
+
+    /// Records the upper bound of a node that is about to be explored
+    pub fn on_bound(&self, bound: isize) {
+        let mut progress = self.progress.lock().unwrap();
+        if bound < progress.best_bound {
+            progress.best_bound = bound;
+            let time = progress.start.elapsed().as_secs_f64();
+            drop(progress);
+            self.emit(SearchEvent::Bound { time, bound: value_to_cost(bound) });
+        }
+    }
+
+    /// Records a new best solution, when the solver is able to report it. A
+    /// value that does not improve on the best one is ignored
+    pub fn on_incumbent(&self, value: isize) {
+        let time = self.time();
+        let mut progress = self.progress.lock().unwrap();
+        if progress.best_value.map(|best| value <= best).unwrap_or(false) {
+            return;
+        }
+        progress.last_improvement = Instant::now();
+        progress.best_value = Some(value);
+        drop(progress);
+        if self.trace {
+            println!("[{time:>10.3}s] new best {}", value_to_cost(value));
+        }
+        self.emit(SearchEvent::Incumbent { time, cost: value_to_cost(value) });
+    }
+
+    /// Records the end of the search
+    pub fn on_completion(&self, is_exact: bool, best_value: Option<isize>) {
+        // the best solution is only reported if the solver did not report it yet
+        if let Some(value) = best_value {
+            self.on_incumbent(value);
+        }
+        self.emit(SearchEvent::Completion { time: self.time(), is_exact, cost: best_value.map(value_to_cost) });
+    }
+
+    fn emit(&self, event: SearchEvent) {
+        if let Some(events) = self.events.as_ref() {
+            let mut events = events.lock().unwrap();
+            let _ = writeln!(events, "{}", serde_json::to_string(&event).unwrap());
+            let _ = events.flush();
+        }
+    }
+
+    fn time(&self) -> f64 {
+        self.progress.lock().unwrap().start.elapsed().as_secs_f64()
+    }
+
+    /// The time elapsed since the best solution last improved, or since the
+    /// start of the search when no solution was found yet
+    pub fn since_last_improvement(&self) -> Duration {
+        self.progress.lock().unwrap().last_improvement.elapsed()
+    }
+}
+
+impl Default for SearchMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A fringe that reports the nodes it hands out to a monitor and to the
+/// statistics of the thread that pops them
+pub struct MonitoredFringe<'a, S> {
+    fringe: Box<dyn Fringe<State = S> + Send + Sync + 'a>,
+    monitor: Arc<SearchMonitor>,
+    stats: Arc<Statistics>,
+}
+
+impl<'a, S> MonitoredFringe<'a, S> {
+    pub fn new(fringe: Box<dyn Fringe<State = S> + Send + Sync + 'a>, monitor: Arc<SearchMonitor>, stats: Arc<Statistics>) -> Self {
+        Self { fringe, monitor, stats }
+    }
+}
+
+impl<'a, S> Fringe for MonitoredFringe<'a, S> {
+    type State = S;
+
+    fn push(&mut self, node: SubProblem<S>) {
+        self.fringe.push(node)
+    }
+
+    fn pop(&mut self) -> Option<SubProblem<S>> {
+        let node = self.fringe.pop();
+        if let Some(node) = node.as_ref() {
+            self.monitor.on_bound(node.ub);
+            self.stats.on_subproblem();
+        }
+        node
+    }
+
+    fn clear(&mut self) {
+        self.fringe.clear()
+    }
+
+    fn len(&self) -> usize {
+        self.fringe.len()
+    }
+}
+
+/// This cutoff stops the search once the best solution has not improved for a
+/// given time. Only the depth-first search reports its solutions as it finds
+/// them, see the module documentation.
+pub struct StallTimeout {
+    monitor: Arc<SearchMonitor>,
+    window: Duration,
+}
+
+impl StallTimeout {
+    pub fn new(monitor: Arc<SearchMonitor>, window: Duration) -> Self {
+        Self { monitor, window }
+    }
+}
+
+impl Cutoff for StallTimeout {
+    fn must_stop(&self) -> bool {
+        self.monitor.since_last_improvement() > self.window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps the events written by a monitor so that the test can read them
+    #[derive(Clone, Default)]
+    struct Events(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Events {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Events {
+        fn incumbents(&self) -> usize {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+                .lines()
+                .filter(|line| line.contains("\"incumbent\""))
+                .count()
+        }
+    }
+
+    #[test]
+    fn start_forgets_the_previous_search() {
+        let events = Events::default();
+        let monitor = SearchMonitor::new().with_events(Box::new(events.clone()));
+
+        for run in 1..=2 {
+            monitor.on_start();
+            monitor.on_bound(-5);
+            monitor.on_completion(true, Some(-10));
+            assert_eq!(events.incumbents(), run);
+        }
+
+        monitor.on_start();
+        assert!(monitor.since_last_improvement() < Duration::from_secs(1));
+        assert_eq!(monitor.progress.lock().unwrap().best_value, None);
+        assert_eq!(monitor.progress.lock().unwrap().best_bound, isize::MAX);
+    }
+
+    #[test]
+    fn only_improving_incumbents_are_reported() {
+        let events = Events::default();
+        let monitor = SearchMonitor::new().with_events(Box::new(events.clone()));
+
+        monitor.on_start();
+        monitor.on_incumbent(-10);
+        monitor.on_incumbent(-10);
+        assert_eq!(events.incumbents(), 1);
+
+        monitor.on_incumbent(-12);
+        assert_eq!(events.incumbents(), 1);
+
+        monitor.on_incumbent(-8);
+        assert_eq!(events.incumbents(), 2);
+        assert_eq!(monitor.progress.lock().unwrap().best_value, Some(-8));
+    }
+}
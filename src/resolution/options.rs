@@ -0,0 +1,78 @@
+//! This module provides a plain-data alternative to the solve builder, e.g.
+//! `solve_instance(instance, SolveOptions { width: 1000, ..Default::default() })`.
+//! Nothing is printed: everything is reported in the returned outcome.
+
+use std::time::Duration;
+
+use serde::{Serialize, Deserialize};
+
+use crate::instance::TalentSchedInstance;
+use crate::resolution::builder::{SolveBuilder, SolveResult, SolverType};
+use crate::resolution::model::Objective;
+
+/// The outcome of `solve_instance`, with the best value and bound expressed
+/// as costs and the scenes in the order in which they are shot
+pub type SolveOutcome = SolveResult;
+
+/// The settings of a solve, which mirror those of the command line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolveOptions {
+    /// The maximum number of nodes in a layer of the decision diagrams
+    pub width: usize,
+    /// The timeout in seconds, None means no time limit
+    pub timeout: Option<f64>,
+    /// The maximum number of expanded nodes, None means no limit
+    pub max_nodes: Option<usize>,
+    /// The number of threads, 0 uses all logical cores
+    pub threads: usize,
+    pub solver: SolverType,
+    pub objective: Objective,
+    /// Only compile one restricted decision diagram, without proving optimality
+    pub heuristic_only: bool,
+    /// Run on a single thread so that the results do not depend on the threads
+    pub deterministic: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            timeout: Some(60.0),
+            max_nodes: None,
+            threads: 0,
+            solver: SolverType::Barrier,
+            objective: Objective::WeightedCost,
+            heuristic_only: false,
+            deterministic: false,
+        }
+    }
+}
+
+impl SolveOptions {
+    /// Returns a solve builder configured with these options
+    pub fn builder(&self) -> SolveBuilder {
+        let mut builder = SolveBuilder::new()
+            .width(self.width)
+            .without_timeout()
+            .threads(self.threads)
+            .solver(self.solver)
+            .objective(self.objective)
+            .heuristic_only(self.heuristic_only)
+            .deterministic(self.deterministic);
+
+        if let Some(timeout) = self.timeout.filter(|t| *t > 0.0) {
+            builder = builder.timeout(Duration::from_secs_f64(timeout));
+        }
+        if let Some(max_nodes) = self.max_nodes {
+            builder = builder.max_nodes(max_nodes);
+        }
+        builder
+    }
+}
+
+/// Solves the given instance with the given options, or returns an error if
+/// the instance is not valid
+pub fn solve_instance(instance: TalentSchedInstance, options: SolveOptions) -> Result<SolveOutcome, String> {
+    options.builder().run(&instance)
+}
@@ -0,0 +1,72 @@
+//! This module writes the result of a solve in machine-readable formats, for
+//! benchmarking harnesses.
+
+use std::{fs::{File, OpenOptions}, io::{BufWriter, Write}, path::Path};
+
+use serde::Serialize;
+
+use crate::resolution::builder::{SolveResult, SolverType};
+
+/// The settings and the result of a solve
+#[derive(Debug, Clone, Serialize)]
+pub struct SolveReport {
+    /// The path of the instance, or - when it was not read from a file
+    pub instance: String,
+    pub solver: SolverType,
+    pub width: usize,
+    pub threads: usize,
+    pub is_exact: bool,
+    pub best_value: Option<isize>,
+    pub best_bound: isize,
+    /// The relative gap between the best value and the bound, absent when no
+    /// solution was found
+    pub gap: Option<f64>,
+    pub duration_secs: f64,
+    pub explored: usize,
+    pub solution: Vec<usize>,
+}
+
+impl SolveReport {
+    pub fn new(instance: &str, solver: SolverType, width: usize, threads: usize, result: &SolveResult) -> Self {
+        Self {
+            instance: instance.to_string(),
+            solver,
+            width,
+            threads,
+            is_exact: result.is_exact,
+            best_value: result.best_value,
+            best_bound: result.best_bound,
+            gap: result.gap(),
+            duration_secs: result.duration,
+            explored: result.explored,
+            solution: result.solution.clone(),
+        }
+    }
+
+    /// Writes the report as a JSON object to the given file
+    pub fn write_json(&self, path: &str) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("cannot create {path}: {e}"))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(|e| format!("cannot write {path}: {e}"))
+    }
+
+    /// Appends the report as a row of the given CSV file, after writing the
+    /// header if the file does not exist yet. The schedule is left out.
+    pub fn append_csv(&self, path: &str) -> Result<(), String> {
+        let exists = Path::new(path).exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)
+            .map_err(|e| format!("cannot open {path}: {e}"))?;
+
+        let mut lines = String::new();
+        if !exists {
+            lines.push_str("instance,solver,width,threads,is_exact,best_value,best_bound,gap,duration,explored\n");
+        }
+        let best_value = self.best_value.map(|v| v.to_string()).unwrap_or_default();
+        let gap = self.gap.map(|g| format!("{g:.6}")).unwrap_or_default();
+        let solver = serde_json::to_value(self.solver).unwrap();
+        lines.push_str(&format!("{},{},{},{},{},{},{},{},{:.3},{}\n",
+            self.instance, solver.as_str().unwrap_or_default(), self.width, self.threads,
+            self.is_exact, best_value, self.best_bound, gap, self.duration_secs, self.explored));
+
+        file.write_all(lines.as_bytes()).map_err(|e| format!("cannot write {path}: {e}"))
+    }
+}
@@ -0,0 +1,43 @@
+//! This module compiles a single restricted decision diagram to quickly find
+//! a good schedule, without proving anything about its optimality.
+
+use std::sync::Arc;
+
+use ddo::{Completion, CompilationInput, CompilationType, Cutoff, DecisionDiagram, DefaultMDDLEL, Problem, SubProblem};
+
+use crate::resolution::model::{TalentSched, TalentSchedRelax, TalentSchedRanking};
+
+/// Compiles a restricted decision diagram of the given width from the root
+/// and returns its best path. The completion is never exact.
+pub fn restricted_solution(problem: &TalentSched, relaxation: &TalentSchedRelax, ranking: &TalentSchedRanking, cutoff: &dyn Cutoff, width: usize) -> (Completion, Option<Vec<usize>>) {
+    let root = SubProblem {
+        state: Arc::new(problem.initial_state()),
+        value: problem.initial_value(),
+        path: vec![],
+        ub: isize::MAX,
+        depth: 0,
+    };
+
+    let input = CompilationInput {
+        comp_type: CompilationType::Restricted,
+        max_width: width,
+        problem,
+        relaxation,
+        ranking,
+        cutoff,
+        residual: &root,
+        best_lb: isize::MIN,
+    };
+
+    let mut mdd = DefaultMDDLEL::default();
+    if mdd.compile(&input).is_err() {
+        return (Completion { is_exact: false, best_value: None }, None);
+    }
+
+    let order = mdd.best_solution().map(|mut solution| {
+        solution.sort_unstable_by_key(|d| d.variable.id());
+        solution.iter().map(|d| d.value as usize).collect()
+    });
+
+    (Completion { is_exact: false, best_value: mdd.best_value() }, order)
+}
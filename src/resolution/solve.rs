@@ -1,52 +1,761 @@
-use std::{fs::File, io::BufReader, time::Duration};
+use std::{fs::File, io::{self, IsTerminal, Write}, sync::Arc, time::Duration};
 
-use clap::Args;
-use ddo::{FixedWidth, TimeBudget, NoDupFringe, MaxUB, ParBarrierSolverFc, Completion, Solver};
+use clap::{Args, ValueEnum};
+use ddo::{Cutoff, Problem, Relaxation};
 
-use crate::resolution::model::{TalentSched, TalentSchedRelax, TalentSchedRanking};
+use crate::resolution::model::{TalentSched, TalentSchedRelax, Objective, ROUNDING_EPSILON, cost_to_value, value_to_cost};
+use crate::resolution::builder::{SolveBuilder, SolverType, FringeType, SolveStatus, SolveResult};
+use crate::resolution::dfs::DepthFirstSearch;
+use crate::resolution::check::{MAX_CHECK_SCENES, check_bound};
+use crate::resolution::cutoff::{AnyCutoff, time_budget, peak_memory};
+use crate::resolution::monitor::SearchMonitor;
+use crate::resolution::width::WidthMode;
+use crate::resolution::report::SolveReport;
+use crate::resolution::manifest::solve_manifest;
+use crate::resolution::table::TableFormat;
 use crate::instance::TalentSchedInstance;
+use crate::resolution::svg::gantt;
+use crate::source::{InstanceSource, FileSource, JsonSource, StdinSource, TextSource};
+use crate::format::InstanceFormat;
+
+/// The ratio between the number of states and the width above which the
+/// solver is unlikely to prove optimality
+const LARGE_INSTANCE_RATIO: f64 = 1e8;
+
+/// The number of schedules compared by --release-actor when --max-solutions is absent
+const RELEASE_CANDIDATES: usize = 1000;
+
+/// How the unknown entries of the actor matrix are resolved before solving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UnknownAs {
+    /// The actor plays in the scene, which gives the worst case cost
+    Present,
+    /// The actor does not play in the scene, which gives the best case cost
+    Absent,
+}
 
 #[derive(Debug, Args)]
 pub struct Solve {
-    /// The path to the instance file
-    #[clap(short, long)]
-    pub instance: String,
+    /// The path to the instance file, in bincode if it ends with .bin and in JSON otherwise.
+    /// When it is - or absent, the instance is read as JSON from the standard input
+    #[clap(short, long, conflicts_with_all=["instance_json", "manifest"])]
+    pub instance: Option<String>,
+    /// The format of the instance file or of the standard input
+    #[clap(long, value_enum, default_value="json")]
+    pub format: InstanceFormat,
+    /// The instance itself, given inline as JSON instead of a path
+    #[clap(long, conflicts_with="manifest")]
+    pub instance_json: Option<String>,
+    /// The path to a JSON list of instances to solve one after the other, each
+    /// entry can override the width and the timeout given on the command line
+    #[clap(long)]
+    pub manifest: Option<String>,
+    /// How the results of the instances of the manifest are printed
+    #[clap(long, value_enum, default_value="table")]
+    pub manifest_format: TableFormat,
     /// max number of nodes in a layeer
     #[clap(short, long, default_value="100")]
     pub width: usize,
-    /// timeout
-    #[clap(short, long, default_value="60")]
+    /// How the width is used: fixed for every subproblem, adaptive to start from it
+    /// and double it each time the bound stalls, or multiplied by the depth (linear)
+    /// or by the number of scenes left (nbvars) of the subproblem
+    #[clap(long, value_enum, default_value="fixed")]
+    pub width_mode: WidthMode,
+    /// timeout in seconds, 0 means no time limit. With --manifest, this is the
+    /// timeout of each instance
+    #[clap(short, long, visible_alias="instance-timeout", default_value="60")]
     pub timeout: u64,
+    /// If present, the time in seconds allowed for all the instances of the manifest,
+    /// the instances that were not started in time are skipped
+    #[clap(long, requires="manifest")]
+    pub total_timeout: Option<u64>,
+    /// If present, stop the search once this many nodes were expanded. When combined
+    /// with the timeout or other limits, the search stops at the first limit reached
+    #[clap(long)]
+    pub max_nodes: Option<usize>,
+    /// The objective function that is minimized
+    #[clap(long, value_enum, default_value="weighted-cost")]
+    pub objective: Objective,
+    /// If present, each day on location after this many days of shooting costs
+    /// --overtime-rate times the daily cost of the actors
+    #[clap(long)]
+    pub deadline: Option<usize>,
+    /// The multiplier of the daily costs after the deadline
+    #[clap(long, default_value="2", requires="deadline", value_parser=clap::value_parser!(u64).range(1..))]
+    pub overtime_rate: u64,
+    /// The algorithm used to solve the instance
+    #[clap(long, value_enum, default_value="barrier")]
+    pub solver: SolverType,
+    /// If present, only compile one restricted decision diagram of the given width
+    /// and report its best schedule, without proving optimality
+    #[clap(long)]
+    pub heuristic_only: bool,
+    /// If present, stop at the first complete schedule found by a greedy descent,
+    /// which is faster than --heuristic-only but usually worse
+    #[clap(long, conflicts_with="heuristic_only")]
+    pub first_feasible: bool,
+    /// If present, print the cost of a greedy schedule before the search, as a
+    /// baseline for the solution found
+    #[clap(long)]
+    pub greedy_warmstart: bool,
+    /// If present, only print the lower bound given by the relaxation at the
+    /// root, along with the trivial one, instead of solving
+    #[clap(long, conflicts_with="check")]
+    pub bound_only: bool,
+    /// If present, check the fast upper bound against a brute force instead of solving
+    #[clap(long)]
+    pub check: bool,
+    /// The maximum number of scenes of an instance that can be checked by brute force,
+    /// at most 25 since the brute force needs memory for 2^n values
+    #[clap(long, default_value="16", value_parser=parse_check_max_scenes)]
+    pub check_max_scenes: usize,
+    /// The number of threads used by the solver, 0 or auto uses all logical cores
+    #[clap(long, default_value="auto", value_parser=parse_threads)]
+    pub threads: usize,
+    /// If present, stop the search once the process uses more than this many MB of memory
+    #[clap(long)]
+    pub max_memory: Option<usize>,
+    /// If present, stop the search once the best solution has not improved for this many
+    /// seconds, only with --solver dfs or --first-feasible since the other solvers do not
+    /// report their solutions before the end of the search
+    #[clap(long)]
+    pub stall_timeout: Option<u64>,
+    /// If present, print the savings compared to shooting the scenes in their natural order
+    #[clap(long)]
+    pub savings: bool,
+    /// The number of times the instance is solved, to report timing statistics.
+    /// The solve fails if the runs do not all find the same best value and bound.
+    /// The result and the statistics printed with --stats are those of the first run
+    #[clap(long, default_value="1")]
+    pub repeat: usize,
+    /// If present, print statistics about the search (e.g. the nodes explored by depth or the work done by each thread).
+    /// The idle time of the threads is not reported, ddo does not expose when its workers wait
+    #[clap(long)]
+    pub stats: bool,
+    /// If present, print the number of nodes and the width of the layers compiled at each depth
+    #[clap(long)]
+    pub layer_stats: bool,
+    /// If present, enumerate up to this many distinct schedules with the best cost found
+    #[clap(long)]
+    pub max_solutions: Option<usize>,
+    /// If present, among the schedules with the best cost found, pick the one that
+    /// releases this actor the earliest (at most --max-solutions of them are compared)
+    #[clap(long)]
+    pub release_actor: Option<usize>,
+    /// Overrides the daily cost of some actors, given as actor=cost pairs separated by commas
+    #[clap(long, value_delimiter=',', value_parser=parse_cost_override)]
+    pub cost_override: Vec<(usize, usize)>,
+    /// How the unknown entries of the actor matrix (null in JSON) are handled by
+    /// the solver, which refuses instances with unknown entries when absent
+    #[clap(long, value_enum)]
+    pub unknown_as: Option<UnknownAs>,
+    /// If present, refuse instances whose actor matrix has entries other than 0
+    /// and 1 instead of treating them as 1
+    #[clap(long)]
+    pub strict_actors: bool,
+    /// The fractional part below which the bounds of the relaxation are rounded down
+    /// instead of up, to absorb the floating point errors, between 0 and 1
+    #[clap(long, default_value_t=ROUNDING_EPSILON, value_parser=parse_rounding_epsilon)]
+    pub rounding_epsilon: f64,
+    /// If present, run the search on a single thread and break the ties between the
+    /// states so that the value, bound and solution reported are the same whatever
+    /// the number of threads given. Several threads cannot give reproducible results.
+    #[clap(long)]
+    pub deterministic: bool,
+    /// If present, the path where to write the search events as JSON lines (- for stderr).
+    /// The bound events are written during the search with all the solvers, but the
+    /// incumbent events are only written as they are found with --solver dfs or
+    /// --first-feasible: the other solvers report their best solution at the end
+    #[clap(long)]
+    pub events_out: Option<String>,
+    /// If present, print the elapsed time and the cost of each new best solution found,
+    /// only with --solver dfs or --first-feasible since the other solvers do not report
+    /// their solutions before the end of the search
+    #[clap(long)]
+    pub trace: bool,
+    /// The type of fringe used to store the open subproblems
+    #[clap(long, value_enum, default_value="nodup")]
+    pub fringe: FringeType,
+    /// If present, print a summary with the cost, the gap and the span of each actor,
+    /// which is the default when the output is a terminal
+    #[clap(long, conflicts_with="no_pretty")]
+    pub pretty: bool,
+    /// If present, never print the summary, even when the output is a terminal
+    #[clap(long)]
+    pub no_pretty: bool,
+    /// If present, print for each position the scene shot, the actors on location
+    /// and the holding cost of those who wait without playing
+    #[clap(long)]
+    pub explain: bool,
+    /// If present, print the schedule with the duration and shooting days of each scene
+    #[clap(long)]
+    pub schedule: bool,
+    /// If present, print which actors are on location on each day
+    #[clap(long)]
+    pub presence_table: bool,
+    /// If present, the path where to write the settings and the result of the solve as JSON
+    #[clap(long)]
+    pub results: Option<String>,
+    /// If present, the path of a CSV file where to append one row with the settings
+    /// and the result of the solve, the header is written when the file is created
+    #[clap(long)]
+    pub csv: Option<String>,
+    /// If present, the path where to write the schedule as an SVG Gantt chart
+    #[clap(long)]
+    pub svg: Option<String>,
     /// If present, the path where to write the output html
     #[clap(short, long)]
     pub output: Option<String>,
 }
 
 impl Solve {
-    pub fn solve(&self) {
-        let instance: TalentSchedInstance = serde_json::from_reader(BufReader::new(File::open(&self.instance).unwrap())).unwrap();
+    pub fn solve(&self) -> SolveStatus {
+        if let Some(manifest) = self.manifest.as_ref() {
+            return match self.builder() {
+                Ok(builder) => solve_manifest(manifest, &builder, self.manifest_format, self.total_timeout.map(Duration::from_secs)),
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    SolveStatus::Failed
+                }
+            };
+        }
+
+        let mut instance = match self.source().load() {
+            Ok(instance) => instance,
+            Err(e) => {
+                eprintln!("error: {e}");
+                return SolveStatus::Failed;
+            }
+        };
+        if self.events_out.is_some() && !self.reports_incumbents() {
+            eprintln!("warning: the best solution is only reported at the end of the search, use --solver dfs to follow it");
+        }
+        if self.stall_timeout.is_some() && !self.reports_incumbents() {
+            eprintln!("error: --stall-timeout needs --solver dfs or --first-feasible");
+            return SolveStatus::Failed;
+        }
+        if self.trace && !self.reports_incumbents() {
+            eprintln!("error: --trace needs --solver dfs or --first-feasible");
+            return SolveStatus::Failed;
+        }
+        if let Err(e) = instance.validate() {
+            eprintln!("error: invalid instance: {e}");
+            return SolveStatus::Failed;
+        }
+
+        let unknown = instance.unknown_entries();
+        if unknown > 0 {
+            match self.unknown_as {
+                Some(unknown_as) => {
+                    let present = unknown_as == UnknownAs::Present;
+                    eprintln!("warning: the actor matrix has {unknown} unknown entries, they are treated as {}", if present { "present" } else { "absent" });
+                    instance.resolve_unknown(present);
+                },
+                None => {
+                    eprintln!("error: the actor matrix has {unknown} unknown entries, use --unknown-as to solve it anyway");
+                    return SolveStatus::Failed;
+                },
+            }
+        }
+
+        let non_binary = instance.non_binary_entries();
+        if non_binary > 0 && self.strict_actors {
+            eprintln!("error: the actor matrix has {non_binary} entries other than 0 and 1");
+            return SolveStatus::Failed;
+        } else if non_binary > 0 {
+            eprintln!("warning: the actor matrix has {non_binary} entries other than 0 and 1, they are treated as 1");
+        }
+        for (actor, cost) in self.cost_override.iter().copied() {
+            if actor >= instance.nb_actors {
+                eprintln!("error: cannot override the cost of actor {actor}, the instance has {} actors", instance.nb_actors);
+                return SolveStatus::Failed;
+            }
+            println!("cost override: actor {actor} {} -> {cost}", instance.cost[actor]);
+            instance.cost[actor] = cost;
+        }
+        if let Some(actor) = self.release_actor.filter(|actor| *actor >= instance.nb_actors) {
+            eprintln!("error: there is no actor {actor}, the instance has {} actors", instance.nb_actors);
+            return SolveStatus::Failed;
+        }
         
-        let problem = TalentSched::new(instance);
-        let relaxation = TalentSchedRelax::new(problem.clone());
+        // the events file is created before solving so that a wrong path does
+        // not waste the search
+        let builder = match self.builder() {
+            Ok(builder) => builder,
+            Err(e) => {
+                eprintln!("error: {e}");
+                return SolveStatus::Failed;
+            }
+        };
+        let problem = builder.problem(instance.clone());
+
+        if self.check {
+            let relaxation = self.relaxation(&problem);
+            return self.check(&problem, &relaxation);
+        }
+        if self.bound_only {
+            self.print_bound(&problem);
+            return SolveStatus::Optimal;
+        }
 
-        let width = FixedWidth(self.width);
-        let cutoff = TimeBudget::new(Duration::from_secs(self.timeout));
-        let ranking = TalentSchedRanking;
-        let mut fringe = NoDupFringe::new(MaxUB::new(&ranking));
+        if !self.heuristic_only && !self.first_feasible {
+            self.warn_if_too_large(&problem);
+        }
+        if self.greedy_warmstart {
+            self.print_greedy(&problem);
+        }
 
-        let mut solver = ParBarrierSolverFc::new(&problem, &relaxation, &ranking, &width, &cutoff, &mut fringe);
+        // the repeats solve a fresh problem each, so that the statistics of
+        // `problem` printed below belong to the first run, whose result is reported
+        let mut results = vec![builder.run_problem(&problem)];
+        for _ in 1..self.repeat {
+            results.push(builder.run_problem(&builder.problem(instance.clone())));
+        }
+        if self.repeat > 1 && !self.print_repeats(&results) {
+            eprintln!("error: the best value or bound differs between the runs");
+            return SolveStatus::Failed;
+        }
 
-        let Completion{best_value, is_exact} = solver.maximize();
+        let mut result = results.swap_remove(0);
+        if let Some(actor) = self.release_actor.filter(|_| !result.solution.is_empty()) {
+            result.solution = self.release_earliest(&problem, &result, actor);
+        }
+        let order = &result.solution;
 
-        let best_value = best_value.map(|v| -v).unwrap_or(isize::MAX);
-        println!("is exact {is_exact}");
+        let best_value = result.best_value.unwrap_or(isize::MAX);
+        println!("is exact {}", result.is_exact);
         println!("best value {best_value}");
+        if let Some(gap) = result.gap() {
+            println!("gap {:.2}%", 100.0 * gap);
+        }
+        println!("explored {}", result.explored);
+        if let Some(limit) = result.stopped_by.as_ref() {
+            println!("stopped by {limit}");
+        }
+        if self.heuristic_only || self.first_feasible {
+            println!("heuristic solution, optimality was not proven");
+        }
+        if result.is_exact && result.best_value.is_none() && problem.instance.contiguous.contains(&true) {
+            println!("infeasible: no schedule satisfies the contiguity constraints");
+        }
 
         let mut sol = String::new();
-        solver.best_solution().unwrap()
-            .iter().map(|d| d.value)
-            .for_each(|v| sol.push_str(&format!("{v} ")));
+        order.iter().for_each(|v| sol.push_str(&format!("{v} ")));
 
         println!("solution: {sol}");
+
+        if self.pretty || (!self.no_pretty && io::stdout().is_terminal() && !order.is_empty()) {
+            self.print_summary(&problem, &result);
+        }
+
+        // a file that cannot be written makes the solve fail, but only once
+        // everything else has been reported
+        let mut write_failed = false;
+        if let Some(path) = self.results.as_ref() {
+            if let Err(e) = self.report(&builder, &result).write_json(path) {
+                eprintln!("error: {e}");
+                write_failed = true;
+            }
+        }
+
+        if let Some(path) = self.csv.as_ref() {
+            if let Err(e) = self.report(&builder, &result).append_csv(path) {
+                eprintln!("error: {e}");
+                write_failed = true;
+            }
+        }
+
+        if self.explain {
+            self.print_explain(&problem, order);
+        }
+
+        if self.schedule {
+            self.print_schedule(&problem.instance, order);
+        }
+
+        if self.presence_table {
+            self.print_presence_table(&problem, order);
+        }
+
+        if let Some(path) = self.svg.as_ref().filter(|_| !order.is_empty()) {
+            if let Err(e) = std::fs::write(path, gantt(&problem, order)) {
+                eprintln!("error: cannot write {path}: {e}");
+                write_failed = true;
+            }
+        }
+
+        if self.savings && !order.is_empty() {
+            self.print_savings(&problem, best_value);
+        }
+
+        if let Some(max_solutions) = self.max_solutions.filter(|_| !order.is_empty()) {
+            let relaxation = self.relaxation(&problem);
+            let cutoff = self.time_limit();
+            let dfs = DepthFirstSearch::new(&problem, &relaxation, &cutoff);
+            let solutions = dfs.enumerate(cost_to_value(best_value), max_solutions);
+
+            println!("{} schedule(s) with cost {best_value}", solutions.len());
+            for solution in solutions {
+                let mut sol = String::new();
+                solution.iter().for_each(|v| sol.push_str(&format!("{v} ")));
+                println!("solution: {sol}");
+            }
+        }
+
+        if self.stats {
+            match peak_memory() {
+                Some(peak) => println!("peak memory {peak} MB"),
+                None => println!("peak memory unavailable on this platform"),
+            }
+            problem.stats.print_merges();
+            problem.stats.print_remaining();
+        }
+
+        if self.stats && builder.nb_threads() > 1 && self.solver == SolverType::Barrier {
+            problem.stats.print_threads();
+        }
+
+        if self.layer_stats {
+            problem.stats.print_layers();
+        }
+
+        if write_failed {
+            SolveStatus::Failed
+        } else {
+            result.status()
+        }
+    }
+
+    /// Returns where the instance is loaded from
+    pub fn source(&self) -> Box<dyn InstanceSource> {
+        match (self.instance.as_ref(), self.instance_json.as_ref()) {
+            (_, Some(json)) => Box::new(JsonSource(json.clone())),
+            (path, None) if self.format != InstanceFormat::Json => Box::new(TextSource(path.filter(|p| *p != "-").cloned(), self.format)),
+            (Some(path), None) if path != "-" => Box::new(FileSource(path.clone())),
+            _ => Box::new(StdinSource),
+        }
+    }
+
+    /// Whether the solver reports each new best solution during the search,
+    /// which only the depth-first search does
+    fn reports_incumbents(&self) -> bool {
+        self.first_feasible || (self.solver == SolverType::Dfs && !self.heuristic_only)
+    }
+
+    fn relaxation(&self, problem: &TalentSched) -> TalentSchedRelax {
+        TalentSchedRelax::new(problem.clone()).with_rounding_epsilon(self.rounding_epsilon)
+    }
+
+    /// Creates a solve builder with the settings given on the command line, or
+    /// returns an error if the events file cannot be created
+    pub fn builder(&self) -> Result<SolveBuilder, String> {
+        let mut builder = SolveBuilder::new()
+            .width(self.width)
+            .width_mode(self.width_mode)
+            .without_timeout()
+            .threads(self.threads)
+            .solver(self.solver)
+            .fringe(self.fringe)
+            .objective(self.objective)
+            .heuristic_only(self.heuristic_only)
+            .first_feasible(self.first_feasible)
+            .deterministic(self.deterministic)
+            .rounding_epsilon(self.rounding_epsilon)
+            .monitor(Arc::new(self.monitor()?));
+
+        if self.timeout > 0 {
+            builder = builder.timeout(Duration::from_secs(self.timeout));
+        }
+        if let Some(deadline) = self.deadline {
+            builder = builder.deadline(deadline, self.overtime_rate as usize);
+        }
+        if let Some(max_nodes) = self.max_nodes {
+            builder = builder.max_nodes(max_nodes);
+        }
+        if let Some(max_memory) = self.max_memory {
+            builder = builder.max_memory(max_memory);
+        }
+        if let Some(stall_timeout) = self.stall_timeout {
+            builder = builder.stall_timeout(Duration::from_secs(stall_timeout));
+        }
+
+        Ok(builder)
+    }
+
+    fn time_limit(&self) -> AnyCutoff {
+        let mut cutoffs: Vec<(&'static str, Box<dyn Cutoff + Send + Sync>)> = vec![];
+        if self.timeout > 0 {
+            cutoffs.push(("timeout", time_budget(Duration::from_secs(self.timeout))));
+        }
+        AnyCutoff::new(cutoffs)
+    }
+
+    fn check(&self, problem: &TalentSched, relaxation: &TalentSchedRelax) -> SolveStatus {
+        if problem.instance.nb_scenes > self.check_max_scenes {
+            println!("cannot check an instance with {} scenes, the maximum is {}", problem.instance.nb_scenes, self.check_max_scenes);
+            return SolveStatus::Failed;
+        }
+
+        let (optimal, violations) = check_bound(problem, relaxation);
+        match optimal {
+            Some(optimal) => println!("optimal value {}", value_to_cost(optimal)),
+            None => println!("infeasible: no schedule satisfies the contiguity constraints"),
+        }
+
+        for violation in violations.iter() {
+            println!("bound violation with remaining scenes {:?}: bound {} > optimal {}", violation.remaining, value_to_cost(violation.bound), value_to_cost(violation.optimal));
+        }
+        println!("{} bound violation(s)", violations.len());
+
+        if violations.is_empty() {
+            SolveStatus::Optimal
+        } else {
+            SolveStatus::Failed
+        }
+    }
+
+    /// Warns when the instance is very unlikely to be solved to optimality with
+    /// the given width. There are 2^n subsets of remaining scenes and each
+    /// decision diagram covers at most `width` of them per layer, so the ratio
+    /// between both is a rough estimate of the work needed to close the gap.
+    fn warn_if_too_large(&self, problem: &TalentSched) {
+        let nb_states = 2.0_f64.powi(problem.instance.nb_scenes as i32);
+        if nb_states / self.width.max(1) as f64 > LARGE_INSTANCE_RATIO {
+            eprintln!("warning: an instance with {} scenes is unlikely to be solved to optimality with width {}, \
+                consider a larger --width or --heuristic-only", problem.instance.nb_scenes, self.width);
+        }
+    }
+
+    /// Returns the schedule with the best cost found that releases the given
+    /// actor the earliest, among at most `max_solutions` of them
+    fn release_earliest(&self, problem: &TalentSched, result: &SolveResult, actor: usize) -> Vec<usize> {
+        let best_value = result.best_value.unwrap_or(isize::MAX);
+        let max_solutions = self.max_solutions.unwrap_or(RELEASE_CANDIDATES);
+
+        let relaxation = self.relaxation(problem);
+        let cutoff = self.time_limit();
+        let dfs = DepthFirstSearch::new(problem, &relaxation, &cutoff);
+
+        let mut best = result.solution.clone();
+        let mut release = problem.instance.release_position(&best, actor);
+        for solution in dfs.enumerate(cost_to_value(best_value), max_solutions) {
+            let position = problem.instance.release_position(&solution, actor);
+            if position < release {
+                best = solution;
+                release = position;
+            }
+        }
+
+        if let Some(position) = release {
+            println!("actor {actor} released after position {position}");
+        }
+
+        best
     }
-}
\ No newline at end of file
+
+    /// Prints the timing statistics of the runs, and returns whether they all
+    /// found the same best value and bound
+    fn print_repeats(&self, results: &[SolveResult]) -> bool {
+        let first = &results[0];
+        let consistent = results.iter().all(|r| r.best_value == first.best_value && r.best_bound == first.best_bound);
+
+        let durations = results.iter().map(|r| r.duration).collect::<Vec<f64>>();
+        let explored = results.iter().map(|r| r.explored as f64).collect::<Vec<f64>>();
+
+        println!("{:>10} {:>12} {:>12} {:>12} {:>12}", "", "min", "mean", "median", "stddev");
+        for (name, values) in [("duration", durations), ("explored", explored)] {
+            let (min, mean, median, stddev) = summary(values);
+            println!("{:>10} {:>12.3} {:>12.3} {:>12.3} {:>12.3}", name, min, mean, median, stddev);
+        }
+        consistent
+    }
+
+    fn print_schedule(&self, instance: &TalentSchedInstance, order: &[usize]) {
+        println!("{:>8} {:>6} {:>8} {:>12}", "position", "scene", "duration", "days");
+
+        let mut day = 0;
+        for (position, scene) in order.iter().copied().enumerate() {
+            let duration = instance.duration[scene];
+            let days = format!("{}-{}", day + 1, day + duration);
+            println!("{:>8} {:>6} {:>8} {:>12}", position, scene, duration, days);
+            day += duration;
+        }
+    }
+
+    fn report(&self, builder: &SolveBuilder, result: &SolveResult) -> SolveReport {
+        let instance = self.instance.as_deref().filter(|_| self.instance_json.is_none()).unwrap_or("-");
+        SolveReport::new(instance, self.solver, self.width, builder.nb_threads(), result)
+    }
+
+    fn print_explain(&self, problem: &TalentSched, order: &[usize]) {
+        println!("{:>8} {:>6} {:>8} {:>10} {:>10}  {}", "position", "scene", "duration", "holding", "total", "on location");
+
+        let mut total = 0;
+        for (position, (scene, present)) in order.iter().copied().zip(problem.presence(order)).enumerate() {
+            let duration = problem.instance.duration[scene];
+            let holding = present.diff(problem.actors[scene]).iter().map(|a| problem.cost[a] * duration).sum::<usize>();
+            total += holding;
+
+            let actors = present.iter()
+                .map(|a| if problem.actors[scene].contains(a) { a.to_string() } else { format!("({a})") })
+                .collect::<Vec<String>>()
+                .join(" ");
+            println!("{:>8} {:>6} {:>8} {:>10} {:>10}  {}", position, scene, duration, holding, total, actors);
+        }
+        println!("actors in parentheses are on location without playing");
+    }
+
+    fn print_summary(&self, problem: &TalentSched, result: &SolveResult) {
+        let order = &result.solution;
+        let presence = problem.presence(order);
+
+        println!();
+        match result.best_value {
+            Some(best_value) => println!("{:<12} {best_value}", "cost"),
+            None => println!("{:<12} -", "cost"),
+        }
+        println!("{:<12} {}", "bound", result.best_bound);
+        if let Some(gap) = result.gap() {
+            println!("{:<12} {:.2}%{}", "gap", 100.0 * gap, if result.is_exact { " (optimal)" } else { "" });
+        }
+        println!("{:<12} {:.3}s", "time", result.duration);
+
+        println!();
+        println!("{:>6} {:>6} {:>6} {:>6} {:>8}", "actor", "from", "to", "days", "cost");
+        for actor in 0..problem.instance.nb_actors {
+            let mut day = 0;
+            let mut span: Option<(usize, usize)> = None;
+            let mut days = 0;
+            for (scene, present) in order.iter().copied().zip(presence.iter()) {
+                let duration = problem.instance.duration[scene];
+                if present.contains(actor) {
+                    let from = span.map(|(from, _)| from).unwrap_or(day + 1);
+                    span = Some((from, day + duration));
+                    days += duration;
+                }
+                day += duration;
+            }
+            if let Some((from, to)) = span {
+                println!("{:>6} {:>6} {:>6} {:>6} {:>8}", actor, from, to, days, problem.actor_cost(order, actor));
+            }
+        }
+    }
+
+    fn print_presence_table(&self, problem: &TalentSched, order: &[usize]) {
+        let nb_actors = problem.instance.nb_actors;
+        let presence = problem.presence(order);
+
+        print!("{:>5} {:>6} ", "day", "scene");
+        (0..nb_actors).for_each(|a| print!("{:>4}", a));
+        println!(" {:>6}", "total");
+
+        let mut day = 0;
+        let mut days_per_actor = vec![0; nb_actors];
+        for (scene, present) in order.iter().copied().zip(presence.iter()) {
+            for _ in 0..problem.instance.duration[scene] {
+                day += 1;
+                print!("{:>5} {:>6} ", day, scene);
+                (0..nb_actors).for_each(|a| print!("{:>4}", if present.contains(a) { "X" } else { "." }));
+                println!(" {:>6}", present.len());
+            }
+            present.iter().for_each(|a| days_per_actor[a] += problem.instance.duration[scene]);
+        }
+
+        print!("{:>12} ", "total");
+        days_per_actor.iter().for_each(|d| print!("{:>4}", d));
+        println!();
+    }
+
+    fn print_savings(&self, problem: &TalentSched, best_value: isize) {
+        let natural = problem.schedule_cost(&(0..problem.instance.nb_scenes).collect::<Vec<usize>>()) as isize;
+        let savings = natural - best_value;
+        let percentage = if natural > 0 { 100.0 * savings as f64 / natural as f64 } else { 0.0 };
+
+        println!("natural order cost {natural}");
+        println!("savings {savings} ({percentage:.2}%)");
+    }
+
+    /// Prints the bound of the relaxation at the root, expressed as a cost,
+    /// and the trivial bound where each actor is only paid for their scenes
+    fn print_bound(&self, problem: &TalentSched) {
+        let relaxation = self.relaxation(problem);
+        let bound = problem.initial_value() + relaxation.fast_upper_bound(&problem.initial_state());
+        println!("appearance bound {}", value_to_cost(problem.initial_value()));
+        println!("lower bound {}", value_to_cost(bound));
+    }
+
+    /// The solvers cannot be given an initial solution, so the greedy schedule
+    /// is only reported
+    fn print_greedy(&self, problem: &TalentSched) {
+        let order = problem.greedy_schedule();
+        let feasible = problem.instance.violations(&order).is_empty();
+        println!("greedy cost {}{}", problem.schedule_cost(&order), if feasible { "" } else { " (breaks a contiguity constraint)" });
+    }
+
+    fn monitor(&self) -> Result<SearchMonitor, String> {
+        let mut monitor = SearchMonitor::new();
+        if self.trace {
+            monitor = monitor.with_trace();
+        }
+        match self.events_out.as_deref() {
+            None => Ok(monitor),
+            Some("-") => Ok(monitor.with_events(Box::new(io::stderr()))),
+            Some(path) => {
+                let file = File::create(path).map_err(|e| format!("cannot create the events file {path}: {e}"))?;
+                let events: Box<dyn Write + Send> = Box::new(file);
+                Ok(monitor.with_events(events))
+            },
+        }
+    }
+}
+
+/// Computes the minimum, mean, median and standard deviation of the values
+fn summary(mut values: Vec<f64>) -> (f64, f64, f64, f64) {
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+    let median = if values.len() % 2 == 0 {
+        (values[values.len() / 2 - 1] + values[values.len() / 2]) / 2.0
+    } else {
+        values[values.len() / 2]
+    };
+
+    (values[0], mean, median, variance.sqrt())
+}
+
+/// Parses a number of threads, where auto is represented by 0
+fn parse_threads(s: &str) -> Result<usize, String> {
+    if s == "auto" {
+        Ok(0)
+    } else {
+        s.parse::<usize>().map_err(|_| format!("expected a number of threads or auto, got {s}"))
+    }
+}
+
+/// Parses a rounding epsilon, which must be in [0, 1)
+fn parse_rounding_epsilon(s: &str) -> Result<f64, String> {
+    let epsilon = s.parse::<f64>().map_err(|_| format!("expected a number, got {s}"))?;
+    if !(0.0..1.0).contains(&epsilon) {
+        return Err(format!("the rounding epsilon must be between 0 and 1, got {epsilon}"));
+    }
+    Ok(epsilon)
+}
+
+/// Parses the maximum number of scenes of the brute force check, which needs
+/// memory for 2^n values
+fn parse_check_max_scenes(s: &str) -> Result<usize, String> {
+    let max_scenes = s.parse::<usize>().map_err(|_| format!("expected a number of scenes, got {s}"))?;
+    if max_scenes > MAX_CHECK_SCENES {
+        return Err(format!("at most {MAX_CHECK_SCENES} scenes can be checked by brute force"));
+    }
+    Ok(max_scenes)
+}
+
+fn parse_cost_override(s: &str) -> Result<(usize, usize), String> {
+    let (actor, cost) = s.split_once('=').ok_or_else(|| format!("expected actor=cost, got {s}"))?;
+    let actor = actor.trim().parse::<usize>().map_err(|_| format!("expected an actor index, got {actor}"))?;
+    let cost = cost.trim().parse::<usize>().map_err(|_| format!("expected a non-negative cost, got {cost}"))?;
+    Ok((actor, cost))
+}
@@ -0,0 +1,172 @@
+//! This module gathers statistics about the decision diagrams compiled
+//! during the search. The counters are atomic so that they can be shared by
+//! all the threads of the parallel solver.
+
+use std::{cell::RefCell, sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}}};
+
+/// Used to give a unique id to each set of statistics
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The statistics of the current thread, along with the id of the set of
+    /// statistics they belong to
+    static THREAD_STATS: RefCell<Option<(usize, Arc<ThreadStats>)>> = RefCell::new(None);
+}
+
+/// The statistics gathered by one thread of the solver.
+///
+/// The idle time of the threads is not part of them: the workers of the
+/// barrier solver wait for each other inside ddo, and the fringe, the only
+/// part of the search they call into while doing so, is accessed under the
+/// lock of the solver, so the waiting is never observed from this crate. An
+/// uneven number of subproblems per thread is the closest sign of idling.
+#[derive(Debug, Default)]
+pub struct ThreadStats {
+    /// The number of nodes expanded by the thread
+    pub explored: AtomicUsize,
+    /// The number of subproblems popped from the fringe by the thread
+    pub subproblems: AtomicUsize,
+}
+
+/// The statistics gathered for one layer of the decision diagrams
+#[derive(Debug, Default)]
+pub struct LayerStats {
+    /// The number of times a layer was compiled at this depth
+    pub layers: AtomicUsize,
+    /// The total number of nodes in those layers
+    pub nodes: AtomicUsize,
+    /// The largest number of nodes in a single layer at this depth
+    pub max_width: AtomicUsize,
+}
+
+/// The statistics gathered during the search
+#[derive(Debug)]
+pub struct Statistics {
+    id: usize,
+    /// The number of nodes expanded in all the decision diagrams
+    pub explored: AtomicUsize,
+    /// The number of merge operations performed by the relaxation
+    pub merges: AtomicUsize,
+    /// The total number of states folded together by those merges
+    pub merged: AtomicUsize,
+    pub layers: Vec<LayerStats>,
+    /// The number of nodes expanded with each number of remaining scenes
+    pub remaining: Vec<AtomicUsize>,
+    /// The statistics of each thread that took part in the search
+    pub threads: Mutex<Vec<Arc<ThreadStats>>>,
+}
+
+impl Statistics {
+    pub fn new(nb_variables: usize) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            explored: AtomicUsize::new(0),
+            merges: AtomicUsize::new(0),
+            merged: AtomicUsize::new(0),
+            layers: (0..=nb_variables).map(|_| LayerStats::default()).collect(),
+            remaining: (0..=nb_variables).map(|_| AtomicUsize::new(0)).collect(),
+            threads: Mutex::new(vec![]),
+        }
+    }
+
+    /// Records the expansion of a node with the given number of remaining scenes
+    pub fn on_expand(&self, remaining: usize) {
+        self.explored.fetch_add(1, Ordering::Relaxed);
+        if let Some(count) = self.remaining.get(remaining) {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+        self.with_thread(|t| t.explored.fetch_add(1, Ordering::Relaxed));
+    }
+
+    /// Records the merge of the given number of states into one
+    pub fn on_merge(&self, nb_states: usize) {
+        self.merges.fetch_add(1, Ordering::Relaxed);
+        self.merged.fetch_add(nb_states, Ordering::Relaxed);
+    }
+
+    /// Records that a subproblem was popped from the fringe
+    pub fn on_subproblem(&self) {
+        self.with_thread(|t| t.subproblems.fetch_add(1, Ordering::Relaxed));
+    }
+
+    /// Gives access to the statistics of the current thread, which are
+    /// registered the first time the thread records something
+    fn with_thread<F: FnOnce(&ThreadStats) -> usize>(&self, f: F) {
+        THREAD_STATS.with(|local| {
+            let mut local = local.borrow_mut();
+            if local.as_ref().map(|(id, _)| *id != self.id).unwrap_or(true) {
+                let stats = Arc::new(ThreadStats::default());
+                self.threads.lock().unwrap().push(stats.clone());
+                *local = Some((self.id, stats));
+            }
+            if let Some((_, stats)) = local.as_ref() {
+                f(stats);
+            }
+        })
+    }
+
+    pub fn explored(&self) -> usize {
+        self.explored.load(Ordering::Relaxed)
+    }
+
+    /// Records a layer of the given width at the given depth
+    pub fn on_layer(&self, depth: usize, width: usize) {
+        if let Some(layer) = self.layers.get(depth) {
+            layer.layers.fetch_add(1, Ordering::Relaxed);
+            layer.nodes.fetch_add(width, Ordering::Relaxed);
+            layer.max_width.fetch_max(width, Ordering::Relaxed);
+        }
+    }
+
+    pub fn print_layers(&self) {
+        println!("{:>6} {:>10} {:>12} {:>10} {:>10}", "depth", "layers", "nodes", "max width", "avg width");
+        for (depth, layer) in self.layers.iter().enumerate() {
+            let layers = layer.layers.load(Ordering::Relaxed);
+            if layers == 0 {
+                continue;
+            }
+            let nodes = layer.nodes.load(Ordering::Relaxed);
+            let max_width = layer.max_width.load(Ordering::Relaxed);
+            println!("{:>6} {:>10} {:>12} {:>10} {:>10.1}", depth, layers, nodes, max_width, nodes as f64 / layers as f64);
+        }
+    }
+
+    pub fn print_merges(&self) {
+        let merges = self.merges.load(Ordering::Relaxed);
+        let merged = self.merged.load(Ordering::Relaxed);
+        if merges == 0 {
+            println!("merges 0");
+        } else {
+            println!("merges {merges} ({merged} states merged, {:.1} per merge)", merged as f64 / merges as f64);
+        }
+    }
+
+    /// Prints the work done by each thread, see `ThreadStats` for why their
+    /// idle time is not reported
+    pub fn print_threads(&self) {
+        println!("{:>6} {:>12} {:>12}", "thread", "explored", "subproblems");
+        for (i, thread) in self.threads.lock().unwrap().iter().enumerate() {
+            let explored = thread.explored.load(Ordering::Relaxed);
+            let subproblems = thread.subproblems.load(Ordering::Relaxed);
+            println!("{:>6} {:>12} {:>12}", i, explored, subproblems);
+        }
+    }
+
+    /// Prints the number of nodes expanded for each number of remaining
+    /// scenes, along with a bar proportional to that number
+    pub fn print_remaining(&self) {
+        const BAR_WIDTH: usize = 50;
+
+        let counts = self.remaining.iter().map(|c| c.load(Ordering::Relaxed)).collect::<Vec<usize>>();
+        let max = counts.iter().copied().max().unwrap_or(0).max(1);
+
+        println!("{:>9} {:>12}", "remaining", "explored");
+        for (remaining, count) in counts.iter().enumerate().rev() {
+            if *count == 0 {
+                continue;
+            }
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(max));
+            println!("{:>9} {:>12} {}", remaining, count, bar);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! This module renders a schedule as an SVG Gantt chart: each actor gets a
+//! horizontal bar spanning the days they are on location, the days where they
+//! actually play being drawn darker.
+
+use std::fmt::Write;
+
+use crate::resolution::model::TalentSched;
+
+const DAY_WIDTH: usize = 20;
+const ROW_HEIGHT: usize = 24;
+const BAR_HEIGHT: usize = 16;
+const LABEL_WIDTH: usize = 80;
+const COST_WIDTH: usize = 80;
+const HEADER_HEIGHT: usize = 24;
+
+/// Returns the SVG Gantt chart of the given schedule
+pub fn gantt(problem: &TalentSched, order: &[usize]) -> String {
+    let instance = &problem.instance;
+    let presence = problem.presence(order);
+    let nb_days = order.iter().map(|s| instance.duration[*s]).sum::<usize>();
+
+    let width = LABEL_WIDTH + nb_days * DAY_WIDTH + COST_WIDTH;
+    let height = HEADER_HEIGHT + instance.nb_actors * ROW_HEIGHT;
+
+    let mut svg = String::new();
+    writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="sans-serif" font-size="12">"#).unwrap();
+
+    // the day gridlines, with the scene shot on each day
+    let mut day = 0;
+    for scene in order.iter().copied() {
+        for _ in 0..instance.duration[scene] {
+            let x = LABEL_WIDTH + day * DAY_WIDTH;
+            writeln!(svg, r##"<line x1="{x}" y1="0" x2="{x}" y2="{height}" stroke="#ddd"/>"##).unwrap();
+            writeln!(svg, r#"<text x="{}" y="16" text-anchor="middle">{scene}</text>"#, x + DAY_WIDTH / 2).unwrap();
+            day += 1;
+        }
+    }
+    let x = LABEL_WIDTH + nb_days * DAY_WIDTH;
+    writeln!(svg, r##"<line x1="{x}" y1="0" x2="{x}" y2="{height}" stroke="#ddd"/>"##).unwrap();
+
+    for actor in 0..instance.nb_actors {
+        let y = HEADER_HEIGHT + actor * ROW_HEIGHT;
+        let bar_y = y + (ROW_HEIGHT - BAR_HEIGHT) / 2;
+        writeln!(svg, r#"<text x="4" y="{}">actor {actor}</text>"#, bar_y + BAR_HEIGHT - 4).unwrap();
+
+        let mut day = 0;
+        for (scene, present) in order.iter().copied().zip(presence.iter()) {
+            let duration = instance.duration[scene];
+            if present.contains(actor) {
+                let color = if problem.actors[scene].contains(actor) { "#4a7ebb" } else { "#b8cce4" };
+                writeln!(svg, r#"<rect x="{}" y="{bar_y}" width="{}" height="{BAR_HEIGHT}" fill="{color}"/>"#,
+                    LABEL_WIDTH + day * DAY_WIDTH, duration * DAY_WIDTH).unwrap();
+            }
+            day += duration;
+        }
+
+        writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, LABEL_WIDTH + nb_days * DAY_WIDTH + 4, bar_y + BAR_HEIGHT - 4, problem.actor_cost(order, actor)).unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
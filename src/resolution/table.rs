@@ -0,0 +1,210 @@
+//! This module formats the results of several solves as a whole table, e.g.
+//! to paste them in a report, an issue or a paper.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::resolution::builder::SolveResult;
+
+/// How the results of several solves are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TableFormat {
+    /// One aligned row per instance
+    Table,
+    /// A markdown table
+    Markdown,
+    /// A LaTeX tabular
+    Latex,
+    /// A JSON array with the result of each instance
+    Json,
+    /// One comma-separated row per instance, with a header
+    Csv,
+}
+
+impl TableFormat {
+    pub fn formatter(&self) -> Box<dyn TableFormatter> {
+        match self {
+            TableFormat::Table => Box::new(Aligned),
+            TableFormat::Markdown => Box::new(Markdown),
+            TableFormat::Latex => Box::new(Latex),
+            TableFormat::Json => Box::new(Json),
+            TableFormat::Csv => Box::new(Csv),
+        }
+    }
+}
+
+/// The result of one instance, as a row of a table
+#[derive(Debug, Clone)]
+pub struct ResultRow {
+    pub instance: String,
+    pub result: SolveResult,
+}
+
+impl ResultRow {
+    fn value(&self) -> String {
+        self.result.best_value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+    }
+
+    /// The gap in percents, without the sign
+    fn gap(&self) -> Option<String> {
+        self.result.gap().map(|gap| format!("{:.2}", 100.0 * gap))
+    }
+}
+
+/// Formats rows of results as a table
+pub trait TableFormatter {
+    fn format(&self, rows: &[ResultRow]) -> String;
+}
+
+/// One aligned row per instance, which can also be printed row by row
+pub struct Aligned;
+
+impl Aligned {
+    pub fn header() -> String {
+        format!("{:<30} {:>8} {:>12} {:>12} {:>10} {:>12}", "instance", "exact", "best value", "best bound", "time", "explored")
+    }
+
+    pub fn row(row: &ResultRow) -> String {
+        let r = &row.result;
+        format!("{:<30} {:>8} {:>12} {:>12} {:>10.3} {:>12}", row.instance, r.is_exact, row.value(), r.best_bound, r.duration, r.explored)
+    }
+}
+
+impl TableFormatter for Aligned {
+    fn format(&self, rows: &[ResultRow]) -> String {
+        let mut table = Self::header();
+        table.push('\n');
+        for row in rows.iter() {
+            table.push_str(&Self::row(row));
+            table.push('\n');
+        }
+        table
+    }
+}
+
+/// A GitHub-flavored markdown table
+pub struct Markdown;
+
+impl TableFormatter for Markdown {
+    fn format(&self, rows: &[ResultRow]) -> String {
+        let mut table = String::new();
+        table.push_str("| instance | value | bound | gap | time | exact |\n");
+        table.push_str("|:---|---:|---:|---:|---:|:---:|\n");
+        for row in rows.iter() {
+            let gap = row.gap().map(|g| format!("{g}%")).unwrap_or_else(|| "-".to_string());
+            table.push_str(&format!("| {} | {} | {} | {} | {:.3} | {} |\n",
+                row.instance.replace('|', "\\|"), row.value(), row.result.best_bound, gap,
+                row.result.duration, if row.result.is_exact { "yes" } else { "no" }));
+        }
+        table
+    }
+}
+
+/// A LaTeX `tabular` environment, with the numbers aligned to the right
+pub struct Latex;
+
+impl Latex {
+    /// Escapes the characters that have a special meaning in LaTeX
+    fn escape(text: &str) -> String {
+        let mut escaped = String::new();
+        for c in text.chars() {
+            match c {
+                '\\' => escaped.push_str("\\textbackslash{}"),
+                '~' => escaped.push_str("\\textasciitilde{}"),
+                '^' => escaped.push_str("\\textasciicircum{}"),
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                },
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+impl TableFormatter for Latex {
+    fn format(&self, rows: &[ResultRow]) -> String {
+        let mut table = String::new();
+        table.push_str("\\begin{tabular}{lrrrrc}\n");
+        table.push_str("\\hline\n");
+        table.push_str("instance & value & bound & gap (\\%) & time (s) & exact \\\\\n");
+        table.push_str("\\hline\n");
+        for row in rows.iter() {
+            table.push_str(&format!("{} & {} & {} & {} & {:.3} & {} \\\\\n",
+                Self::escape(&row.instance), row.value(), row.result.best_bound,
+                row.gap().unwrap_or_else(|| "-".to_string()), row.result.duration,
+                if row.result.is_exact { "yes" } else { "no" }));
+        }
+        table.push_str("\\hline\n");
+        table.push_str("\\end{tabular}\n");
+        table
+    }
+}
+
+/// A JSON array with the result of each instance and its gap
+pub struct Json;
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    instance: &'a str,
+    #[serde(flatten)]
+    result: &'a SolveResult,
+    gap: Option<f64>,
+}
+
+impl TableFormatter for Json {
+    fn format(&self, rows: &[ResultRow]) -> String {
+        let rows = rows.iter()
+            .map(|row| JsonRow { instance: &row.instance, result: &row.result, gap: row.result.gap() })
+            .collect::<Vec<JsonRow>>();
+        let mut table = serde_json::to_string_pretty(&rows).unwrap();
+        table.push('\n');
+        table
+    }
+}
+
+/// One comma-separated row per instance, with a header. The missing values
+/// are left empty.
+pub struct Csv;
+
+impl TableFormatter for Csv {
+    fn format(&self, rows: &[ResultRow]) -> String {
+        let mut table = String::new();
+        table.push_str("instance,is_exact,best_value,best_bound,gap,duration,explored\n");
+        for row in rows.iter() {
+            let r = &row.result;
+            let best_value = r.best_value.map(|v| v.to_string()).unwrap_or_default();
+            let gap = r.gap().map(|g| format!("{g:.6}")).unwrap_or_default();
+            table.push_str(&format!("{},{},{},{},{},{:.3},{}\n", row.instance, r.is_exact, best_value, r.best_bound, gap, r.duration, r.explored));
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_format_lists_every_instance() {
+        let result = |best_value: Option<isize>| SolveResult {
+            is_exact: best_value.is_some(),
+            best_value,
+            best_bound: 10,
+            solution: vec![],
+            duration: 0.5,
+            explored: 3,
+            stopped_by: None,
+        };
+        let rows = vec![
+            ResultRow { instance: "a.json".to_string(), result: result(Some(10)) },
+            ResultRow { instance: "b.json".to_string(), result: result(None) },
+        ];
+
+        for format in TableFormat::value_variants() {
+            let table = format.formatter().format(&rows);
+            assert!(table.contains("a.json") && table.contains("b.json"), "{format:?}: {table}");
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! This module provides the width heuristics that set the maximum width of
+//! the decision diagrams compiled for each subproblem.
+
+use std::sync::Mutex;
+
+use clap::ValueEnum;
+use ddo::{SubProblem, WidthHeuristic};
+
+use crate::resolution::model::TalentSchedState;
+
+/// How the maximum width of the decision diagrams is chosen. ddo sets the
+/// width once for the whole diagram compiled from a subproblem, so the depth
+/// used by the scaled modes is the one of the subproblem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WidthMode {
+    /// The same width for every subproblem
+    Fixed,
+    /// Starts from the given width and doubles it each time the bound stalls
+    Adaptive,
+    /// The given width multiplied by the depth of the subproblem plus one
+    Linear,
+    /// The given width multiplied by the number of scenes left to schedule
+    #[value(name = "nbvars")]
+    NbVars,
+}
+
+/// A width that grows with the depth of the subproblem
+pub struct LinearWidth(pub usize);
+
+impl WidthHeuristic<TalentSchedState> for LinearWidth {
+    fn max_width(&self, state: &SubProblem<TalentSchedState>) -> usize {
+        self.0.saturating_mul(state.depth + 1)
+    }
+}
+
+/// A width proportional to the number of scenes left to schedule
+pub struct NbVarsWidth {
+    pub multiplier: usize,
+    pub nb_variables: usize,
+}
+
+impl WidthHeuristic<TalentSchedState> for NbVarsWidth {
+    fn max_width(&self, state: &SubProblem<TalentSchedState>) -> usize {
+        self.multiplier.saturating_mul(self.nb_variables.saturating_sub(state.depth).max(1))
+    }
+}
+
+/// The number of subproblems compiled without improving the bound after
+/// which the adaptive width doubles
+const PATIENCE: usize = 100;
+
+/// The largest factor by which the adaptive width can grow
+const MAX_GROWTH: usize = 16;
+
+struct AdaptiveProgress {
+    width: usize,
+    best_bound: isize,
+    stalled: usize,
+}
+
+/// A width that grows when the bound stays the same. The subproblems are
+/// compiled by decreasing upper bound, so the bound of the current subproblem
+/// is the best bound of the whole problem: when it does not decrease for a
+/// while, the diagrams are too narrow to prune the subproblems left, and
+/// doubling the width gives tighter relaxed bounds.
+pub struct AdaptiveWidth {
+    max_width: usize,
+    progress: Mutex<AdaptiveProgress>,
+}
+
+impl AdaptiveWidth {
+    pub fn new(width: usize) -> Self {
+        Self {
+            max_width: width.saturating_mul(MAX_GROWTH),
+            progress: Mutex::new(AdaptiveProgress { width, best_bound: isize::MAX, stalled: 0 }),
+        }
+    }
+}
+
+impl WidthHeuristic<TalentSchedState> for AdaptiveWidth {
+    fn max_width(&self, state: &SubProblem<TalentSchedState>) -> usize {
+        let mut progress = self.progress.lock().unwrap();
+
+        if state.ub < progress.best_bound {
+            progress.best_bound = state.ub;
+            progress.stalled = 0;
+        } else {
+            progress.stalled += 1;
+            if progress.stalled >= PATIENCE && progress.width < self.max_width {
+                progress.width = (progress.width * 2).min(self.max_width);
+                progress.stalled = 0;
+            }
+        }
+
+        progress.width
+    }
+}
@@ -0,0 +1,40 @@
+//! This module reads the schedules produced by the solver or other tools.
+
+use std::fs;
+
+/// Parses a schedule given as a list of scene indices separated by
+/// whitespaces or commas, optionally preceded by the `solution:` label
+/// printed by the solver. Any other token is an error.
+pub fn parse_solution(text: &str) -> Result<Vec<usize>, String> {
+    let text = text.trim_start();
+    let text = text.strip_prefix("solution:").unwrap_or(text);
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse::<usize>().map_err(|_| format!("'{token}' is not a scene index")))
+        .collect()
+}
+
+/// Reads a schedule from the given file
+pub fn read_solution(path: &str) -> Result<Vec<usize>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    parse_solution(&text).map_err(|e| format!("{path}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_separated_indices() {
+        assert_eq!(parse_solution("2 0,1\n3"), Ok(vec![2, 0, 1, 3]));
+        assert_eq!(parse_solution("solution: 1, 0"), Ok(vec![1, 0]));
+        assert_eq!(parse_solution(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn rejects_unparsable_tokens() {
+        assert!(parse_solution("1 two 3").is_err());
+        assert!(parse_solution("1 -2").is_err());
+        assert!(parse_solution("cost: 10").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+//! This module defines where instances are loaded from. Library users can
+//! implement `InstanceSource` to load instances from anywhere else (e.g. a
+//! database or the network).
+
+use std::{fs, io::{self, Read}};
+
+use crate::format::InstanceFormat;
+use crate::instance::TalentSchedInstance;
+
+/// A place from which an instance can be loaded
+pub trait InstanceSource {
+    /// Loads the instance, or describes why it could not be loaded
+    fn load(&self) -> Result<TalentSchedInstance, String>;
+}
+
+/// An instance stored in a file, in bincode if its extension is `.bin` and
+/// in JSON otherwise
+pub struct FileSource(pub String);
+
+impl InstanceSource for FileSource {
+    fn load(&self) -> Result<TalentSchedInstance, String> {
+        TalentSchedInstance::try_read(&self.0)
+    }
+}
+
+/// An instance given directly as a JSON string
+pub struct JsonSource(pub String);
+
+impl InstanceSource for JsonSource {
+    fn load(&self) -> Result<TalentSchedInstance, String> {
+        serde_json::from_str(&self.0).map_err(|e| format!("invalid inline instance: {e}"))
+    }
+}
+
+/// An instance read as JSON from the standard input
+pub struct StdinSource;
+
+impl InstanceSource for StdinSource {
+    fn load(&self) -> Result<TalentSchedInstance, String> {
+        serde_json::from_reader(io::stdin().lock()).map_err(|e| format!("invalid instance on the standard input: {e}"))
+    }
+}
+
+/// An instance in one of the text formats, read from the given file or from
+/// the standard input when there is none
+pub struct TextSource(pub Option<String>, pub InstanceFormat);
+
+impl InstanceSource for TextSource {
+    fn load(&self) -> Result<TalentSchedInstance, String> {
+        match self.0.as_ref() {
+            Some(path) => {
+                let text = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+                self.1.parse(&text).map_err(|e| format!("invalid instance in {path}: {e}"))
+            },
+            None => {
+                let mut text = String::new();
+                io::stdin().lock().read_to_string(&mut text).map_err(|e| format!("cannot read the standard input: {e}"))?;
+                self.1.parse(&text).map_err(|e| format!("invalid instance on the standard input: {e}"))
+            },
+        }
+    }
+}
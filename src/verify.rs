@@ -0,0 +1,76 @@
+//! This module checks that a schedule is valid for an instance and
+//! recomputes its cost, independently of the solver.
+
+use clap::Args;
+
+use crate::instance::TalentSchedInstance;
+use crate::solution::{parse_solution, read_solution};
+
+#[derive(Debug, Args)]
+pub struct Verify {
+    /// The path to the instance file
+    #[clap(short, long)]
+    pub instance: String,
+    /// The path to the solution file
+    pub solution: String,
+}
+
+impl Verify {
+    /// Prints the cost of the schedule and each constraint it violates.
+    /// Returns whether the schedule satisfies all the constraints, or an
+    /// error if the instance or the solution cannot be read.
+    pub fn verify(&self) -> Result<bool, String> {
+        let instance = read_instance(&self.instance)?;
+        let order = read_solution(&self.solution)?;
+        Ok(verify_order(&instance, &order))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct Check {
+    /// The path to the instance file
+    #[clap(short, long)]
+    pub instance: String,
+    /// The schedule, either as scene indices separated by whitespaces or
+    /// commas, or as the path to a file containing them
+    #[clap(short, long)]
+    pub solution: String,
+}
+
+impl Check {
+    /// Verifies the schedule like `verify`, where the schedule can also be
+    /// given inline. Returns whether it satisfies all the constraints.
+    pub fn check(&self) -> Result<bool, String> {
+        let instance = read_instance(&self.instance)?;
+        let order = if std::path::Path::new(&self.solution).is_file() {
+            read_solution(&self.solution)?
+        } else {
+            parse_solution(&self.solution)?
+        };
+        Ok(verify_order(&instance, &order))
+    }
+}
+
+fn read_instance(path: &str) -> Result<TalentSchedInstance, String> {
+    let instance = TalentSchedInstance::try_read(path)?;
+    instance.check_dimensions().map_err(|e| format!("invalid instance: {e}"))?;
+    Ok(instance)
+}
+
+/// Prints the cost of the schedule and each constraint it violates, and
+/// returns whether the schedule satisfies all the constraints
+fn verify_order(instance: &TalentSchedInstance, order: &[usize]) -> bool {
+    let violations = instance.violations(order);
+    for violation in violations.iter() {
+        println!("violation: {violation}");
+    }
+
+    if violations.is_empty() {
+        println!("valid");
+        println!("cost {}", instance.schedule_cost(order));
+    } else {
+        println!("invalid: {} violation(s)", violations.len());
+    }
+
+    violations.is_empty()
+}
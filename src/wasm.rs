@@ -0,0 +1,87 @@
+//! This module exposes the solver to JavaScript when compiled to WebAssembly
+//! with the `wasm` feature.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::instance::TalentSchedInstance;
+use crate::resolution::{SolveBuilder, SolverType, Objective};
+
+/// The settings of a solve, all of them are optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct WasmConfig {
+    width: Option<usize>,
+    /// The timeout in seconds
+    timeout: Option<f64>,
+    max_nodes: Option<usize>,
+    solver: Option<SolverType>,
+    objective: Option<Objective>,
+    heuristic_only: bool,
+}
+
+/// Solves the instance given as JSON with the settings given as JSON, and
+/// returns the result of the solve as JSON. When the instance or the settings
+/// are not valid, `{"error": "..."}` is returned instead. Under WebAssembly,
+/// the search always runs on a single thread.
+#[wasm_bindgen]
+pub fn solve(instance_json: &str, config_json: &str) -> String {
+    let instance = serde_json::from_str::<TalentSchedInstance>(instance_json)
+        .map_err(|e| e.to_string())
+        .and_then(|instance| instance.validate().map(|_| instance));
+    let instance = match instance {
+        Ok(instance) => instance,
+        Err(e) => return error(&format!("invalid instance: {e}")),
+    };
+    let config: WasmConfig = match serde_json::from_str(config_json) {
+        Ok(config) => config,
+        Err(e) => return error(&format!("invalid config: {e}")),
+    };
+
+    let mut builder = SolveBuilder::new()
+        .threads(1)
+        .without_timeout()
+        .heuristic_only(config.heuristic_only);
+
+    if let Some(width) = config.width {
+        builder = builder.width(width);
+    }
+    if let Some(timeout) = config.timeout {
+        builder = builder.timeout(Duration::from_secs_f64(timeout));
+    }
+    if let Some(max_nodes) = config.max_nodes {
+        builder = builder.max_nodes(max_nodes);
+    }
+    if let Some(solver) = config.solver {
+        builder = builder.solver(solver);
+    }
+    if let Some(objective) = config.objective {
+        builder = builder.objective(objective);
+    }
+
+    match builder.run(&instance) {
+        Ok(result) => serde_json::to_string(&result).unwrap(),
+        Err(e) => error(&format!("invalid instance: {e}")),
+    }
+}
+
+fn error(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_instances_are_reported_as_errors() {
+        let mismatched = r#"{"nb_scenes": 2, "nb_actors": 1, "cost": [1], "duration": [1], "actors": [[1, 0]]}"#;
+        let output: serde_json::Value = serde_json::from_str(&solve(mismatched, "{}")).unwrap();
+        assert!(output["error"].as_str().unwrap().starts_with("invalid instance"));
+
+        let output: serde_json::Value = serde_json::from_str(&solve("{", "{}")).unwrap();
+        assert!(output.get("error").is_some());
+    }
+}